@@ -1,6 +1,6 @@
 use crate::commands::utils::structs::Command;
-use crate::commands::{film, letterboxd};
+use crate::commands::{film, letterboxd, person};
 
 pub fn all() -> Vec<Command> {
-    vec![letterboxd::base(), film::base()]
+    vec![letterboxd::base(), film::base(), person::person()]
 }
@@ -1,5 +1,12 @@
 #![allow(dead_code)]
-use crate::commands::utils::{paginator, structs, tmdb_util};
+use crate::commands::utils::{
+    autocomplete, ctx_util, keys, paginator,
+    picker::{self, Choice, Picked},
+    render, structs,
+    tmdb_util::{self, TmdbCandidate, TmdbFilm},
+};
+use crate::config;
+use futures::future::join_all;
 //use poise::serenity_prelude as serenity;
 
 type Context<'a> = poise::Context<'a, structs::Data, Error>;
@@ -9,56 +16,260 @@ async fn sleep(secs: u64) {
     tokio::time::sleep(tokio::time::Duration::from_secs(secs)).await;
 }
 
+/// Settles on one TMDB search result, asking the author when several films share the title.
+async fn pick_film(
+    ctx: Context<'_>,
+    title: &str,
+    year: Option<i32>,
+) -> Result<Picked<TmdbCandidate>, Error> {
+    let tmdb = &ctx.data().tmdb;
+    let candidates = tmdb.search(title, year).await?;
+    // TMDB already filters by year, so a given year is as good as a pick.
+    if year.is_some() || !picker::is_ambiguous(title, candidates.iter().map(|c| c.title.as_str()))
+    {
+        return Ok(match candidates.into_iter().next() {
+            Some(candidate) => Picked::One(candidate),
+            None => Picked::NotFound,
+        });
+    }
+    // Looked up together, one after the other would hold the menu up for each of them.
+    let shown = &candidates[..candidates.len().min(picker::MAX_CHOICES)];
+    let directors = join_all(shown.iter().map(|candidate| tmdb.director(candidate.id))).await;
+    let choices = shown
+        .iter()
+        .zip(directors)
+        .map(|(candidate, director)| {
+            let director = director
+                .ok()
+                .flatten()
+                .map(|d| format!("Directed by {}", d));
+            let description = [candidate.original().map(str::to_string), director];
+            Choice {
+                label: candidate.display_title(),
+                description: description
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(" • "),
+            }
+        })
+        .collect::<Vec<_>>();
+    let prompt = format!("There are a few films called `{}`, which one?", title);
+    Ok(match picker::pick(ctx, &prompt, &choices).await? {
+        Some(i) if i < candidates.len() => Picked::Chosen(candidates[i].clone()),
+        _ => Picked::Cancelled,
+    })
+}
+
+/// Gets the title and `kind` images (`posters` or `backdrops`) of a film, going through the cache.
+async fn get_images(
+    ctx: Context<'_>,
+    title: &str,
+    year: Option<i32>,
+    kind: &str,
+) -> Result<Picked<(String, Vec<String>)>, Error> {
+    let cache = if kind == "posters" {
+        &ctx.data().poster_cache
+    } else {
+        &ctx.data().backdrop_cache
+    };
+    let key = keys::title(title, year);
+    if let Some(images) = cache.get(&key) {
+        return Ok(Picked::One(images));
+    }
+    let (film, remember) = match pick_film(ctx, title, year).await? {
+        Picked::One(film) => (film, true),
+        Picked::Chosen(film) => (film, false),
+        Picked::NotFound => return Ok(Picked::NotFound),
+        Picked::Cancelled => return Ok(Picked::Cancelled),
+    };
+    let canonical = keys::tmdb_id(film.id);
+    let images = match cache.get(&canonical) {
+        Some(images) => images,
+        None => {
+            let images = (
+                film.display_title(),
+                ctx.data().tmdb.images(film.id, kind).await?,
+            );
+            cache.insert(&canonical, images.clone());
+            images
+        }
+    };
+    Ok(if remember {
+        cache.alias(key, canonical);
+        Picked::One(images)
+    } else {
+        Picked::Chosen(images)
+    })
+}
+
+/// Gets a film's TMDB details, going through the cache.
+async fn get_details(
+    ctx: Context<'_>,
+    title: &str,
+    year: Option<i32>,
+) -> Result<Picked<TmdbFilm>, Error> {
+    let cache = &ctx.data().details_cache;
+    let key = keys::title(title, year);
+    if let Some(film) = cache.get(&key) {
+        return Ok(Picked::One(film));
+    }
+    let (candidate, remember) = match pick_film(ctx, title, year).await? {
+        Picked::One(candidate) => (candidate, true),
+        Picked::Chosen(candidate) => (candidate, false),
+        Picked::NotFound => return Ok(Picked::NotFound),
+        Picked::Cancelled => return Ok(Picked::Cancelled),
+    };
+    let film = ctx.data().details(candidate.id).await?;
+    Ok(if remember {
+        cache.alias(key, keys::tmdb_id(candidate.id));
+        Picked::One(film)
+    } else {
+        Picked::Chosen(film)
+    })
+}
+
 /// Base film commands.
-#[poise::command(slash_command, rename = "film", subcommands("backdrops", "posters"))]
+#[poise::command(
+    slash_command,
+    rename = "film",
+    subcommands("backdrops", "cast", "info", "posters", "region", "trailer", "watch")
+)]
 pub async fn base(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Get a film's details from TMDB.
+#[poise::command(slash_command)]
+pub async fn info(
+    ctx: Context<'_>,
+    #[description = "The film title."]
+    #[autocomplete = "autocomplete::film_title"]
+    title: String,
+    #[description = "The release year of the film."]
+    #[min = 1900]
+    year: Option<i32>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let (title, year) = autocomplete::split_year(&title, year);
+    if ctx_util::reject_future_year(ctx, year).await? {
+        return Ok(());
+    }
+    let color = ctx_util::color(ctx).await;
+    let film = match get_details(ctx, &title, year).await? {
+        Picked::One(film) | Picked::Chosen(film) => film,
+        Picked::NotFound => {
+            let error_message = ctx.say(format!("Couldn't find `{}` film.", title)).await?;
+            sleep(5).await;
+            error_message.delete(ctx).await?;
+            return Ok(());
+        }
+        Picked::Cancelled => return Ok(()),
+    };
+    let region = ctx.data().region(ctx.guild_id().map(|id| id.0));
+    ctx.send(|m| m.embed(|e| render::tmdb_film(e, &film, &region, color)))
+        .await?;
+    Ok(())
+}
+
+/// Get a film's full cast and crew.
+#[poise::command(slash_command)]
+pub async fn cast(
+    ctx: Context<'_>,
+    #[description = "The film title."]
+    #[autocomplete = "autocomplete::film_title"]
+    title: String,
+    #[description = "The release year of the film."]
+    #[min = 1900]
+    year: Option<i32>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let (title, year) = autocomplete::split_year(&title, year);
+    if ctx_util::reject_future_year(ctx, year).await? {
+        return Ok(());
+    }
+    let color = ctx_util::color(ctx).await;
+    let film = match get_details(ctx, &title, year).await? {
+        Picked::One(film) | Picked::Chosen(film) => film,
+        Picked::NotFound => {
+            let error_message = ctx.say(format!("Couldn't find `{}` film.", title)).await?;
+            sleep(5).await;
+            error_message.delete(ctx).await?;
+            return Ok(());
+        }
+        Picked::Cancelled => return Ok(()),
+    };
+    let cast = film
+        .credits
+        .cast
+        .iter()
+        .map(|member| {
+            let character = member.character.as_deref().unwrap_or_default();
+            if character.is_empty() {
+                format!("**{}**", member.name)
+            } else {
+                format!("**{}** as {}", member.name, character)
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut pages = paginator::text_pages(
+        &format!("{} • Cast", film.display_title()),
+        &cast,
+        config::CREDITS_PER_PAGE,
+    );
+    for (department, members) in film.credits.departments() {
+        let crew = members
+            .iter()
+            .map(|member| format!("**{}**, {}", member.name, member.job))
+            .collect::<Vec<_>>();
+        pages.extend(paginator::text_pages(
+            &format!("{} • {}", film.display_title(), department),
+            &crew,
+            config::CREDITS_PER_PAGE,
+        ));
+    }
+    if pages.is_empty() {
+        let error_message = ctx
+            .say(format!(
+                "`{}` has no credits on TMDB.",
+                film.display_title()
+            ))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    for page in &mut pages {
+        page.url = Some(film.url());
+        page.thumbnail = film.poster();
+    }
+    paginator::start(ctx, color, *ctx.author().id.as_u64(), &pages).await?;
+    Ok(())
+}
+
 /// Get a film's backdrops.
 #[poise::command(slash_command)]
 pub async fn backdrops(
     ctx: Context<'_>,
-    #[description = "The film title."] title: String,
+    #[description = "The film title."]
+    #[autocomplete = "autocomplete::film_title"]
+    title: String,
     #[description = "The release year of the film."]
     #[min = 1900]
-    #[max = 2023]
     year: Option<i32>,
 ) -> Result<(), Error> {
     ctx.defer().await?;
-    let color = ctx
-        .author_member()
-        .await
-        .unwrap()
-        .colour(&ctx.serenity_context().cache)
-        .unwrap();
-    let cache = &ctx.data().backdrop_cache;
-    let backdrops = if cache.read().unwrap().contains_key(&title) {
-        cache.read().unwrap().get(&title).cloned().unwrap()
-    } else {
-        let title_clone = title.clone();
-        let year = year.unwrap_or(0);
-        let handle = tokio::runtime::Handle::current();
-        let backdrops = tokio::task::spawn_blocking(move || {
-            tmdb_util::get_images(title_clone, year, "backdrops").unwrap()
-        })
-        .await
-        .unwrap();
-        drop(handle);
-        ctx.data()
-            .backdrop_cache
-            .write()
-            .unwrap()
-            .insert(title.clone(), backdrops);
-        ctx.data()
-            .backdrop_cache
-            .read()
-            .unwrap()
-            .get(&title)
-            .cloned()
-            .unwrap()
+    let (title, year) = autocomplete::split_year(&title, year);
+    if ctx_util::reject_future_year(ctx, year).await? {
+        return Ok(());
+    }
+    let color = ctx_util::color(ctx).await;
+    let backdrops = match get_images(ctx, &title, year, "backdrops").await? {
+        Picked::One(backdrops) | Picked::Chosen(backdrops) => backdrops,
+        Picked::NotFound => (String::new(), vec![]),
+        Picked::Cancelled => return Ok(()),
     };
-    if !backdrops.0.is_empty() {
+    if !backdrops.1.is_empty() {
         paginator::start_images(
             ctx,
             &backdrops.0,
@@ -79,46 +290,25 @@ pub async fn backdrops(
 #[poise::command(slash_command)]
 pub async fn posters(
     ctx: Context<'_>,
-    #[description = "The film title."] title: String,
+    #[description = "The film title."]
+    #[autocomplete = "autocomplete::film_title"]
+    title: String,
     #[description = "The release year of the film."]
     #[min = 1900]
-    #[max = 2023]
     year: Option<i32>,
 ) -> Result<(), Error> {
     ctx.defer().await?;
-    let color = ctx
-        .author_member()
-        .await
-        .unwrap()
-        .colour(&ctx.serenity_context().cache)
-        .unwrap();
-    let cache = &ctx.data().poster_cache;
-    let posters = if cache.read().unwrap().contains_key(&title) {
-        cache.read().unwrap().get(&title).cloned().unwrap()
-    } else {
-        let title_clone = title.clone();
-        let year = year.unwrap_or(0);
-        let handle = tokio::runtime::Handle::current();
-        let posters = tokio::task::spawn_blocking(move || {
-            tmdb_util::get_images(title_clone, year, "posters").unwrap()
-        })
-        .await
-        .unwrap();
-        drop(handle);
-        ctx.data()
-            .poster_cache
-            .write()
-            .unwrap()
-            .insert(title.clone(), posters);
-        ctx.data()
-            .poster_cache
-            .read()
-            .unwrap()
-            .get(&title)
-            .cloned()
-            .unwrap()
+    let (title, year) = autocomplete::split_year(&title, year);
+    if ctx_util::reject_future_year(ctx, year).await? {
+        return Ok(());
+    }
+    let color = ctx_util::color(ctx).await;
+    let posters = match get_images(ctx, &title, year, "posters").await? {
+        Picked::One(posters) | Picked::Chosen(posters) => posters,
+        Picked::NotFound => (String::new(), vec![]),
+        Picked::Cancelled => return Ok(()),
     };
-    if !posters.0.is_empty() {
+    if !posters.1.is_empty() {
         paginator::start_images(
             ctx,
             &posters.0,
@@ -134,3 +324,166 @@ pub async fn posters(
     }
     Ok(())
 }
+
+/// Watch a film's trailers and other videos.
+#[poise::command(slash_command)]
+pub async fn trailer(
+    ctx: Context<'_>,
+    #[description = "The film title."]
+    #[autocomplete = "autocomplete::film_title"]
+    title: String,
+    #[description = "The release year of the film."]
+    #[min = 1900]
+    year: Option<i32>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let (title, year) = autocomplete::split_year(&title, year);
+    if ctx_util::reject_future_year(ctx, year).await? {
+        return Ok(());
+    }
+    let color = ctx_util::color(ctx).await;
+    let film = match pick_film(ctx, &title, year).await? {
+        Picked::One(film) | Picked::Chosen(film) => film,
+        Picked::NotFound => {
+            let error_message = ctx.say(format!("Couldn't find `{}` film.", title)).await?;
+            sleep(5).await;
+            error_message.delete(ctx).await?;
+            return Ok(());
+        }
+        Picked::Cancelled => return Ok(()),
+    };
+    // Discord locales are like `en-US` or `fr`, TMDB wants the language part.
+    let language = ctx
+        .locale()
+        .and_then(|locale| locale.split('-').next())
+        .unwrap_or("en");
+    let videos = ctx.data().tmdb.videos(film.id, language).await?;
+    let pages = tmdb_util::rank_videos(videos, language)
+        .iter()
+        .filter_map(|video| {
+            let url = video.url()?;
+            let official = if video.official { " • Official" } else { "" };
+            Some(paginator::Page {
+                title: film.display_title(),
+                url: Some(url.clone()),
+                description: Some(format!("**{}**\n{}{}", video.name, video.kind, official)),
+                content: Some(url),
+                ..Default::default()
+            })
+        })
+        .collect::<Vec<_>>();
+    if pages.is_empty() {
+        let error_message = ctx
+            .say(format!("`{}` has no videos on TMDB.", film.display_title()))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    paginator::start(ctx, color, *ctx.author().id.as_u64(), &pages).await?;
+    Ok(())
+}
+
+/// Find where a film is streaming, for rent or for sale.
+#[poise::command(slash_command)]
+pub async fn watch(
+    ctx: Context<'_>,
+    #[description = "The film title."]
+    #[autocomplete = "autocomplete::film_title"]
+    title: String,
+    #[description = "The release year of the film."]
+    #[min = 1900]
+    year: Option<i32>,
+    #[description = "A two-letter country code like `GB`, defaults to the server's."]
+    region: Option<String>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let (title, year) = autocomplete::split_year(&title, year);
+    if ctx_util::reject_future_year(ctx, year).await? {
+        return Ok(());
+    }
+    let region = match region {
+        Some(code) => match tmdb_util::region(&code) {
+            Some(region) => region,
+            None => {
+                let error_message = ctx
+                    .say(format!("`{}` isn't a two-letter country code.", code))
+                    .await?;
+                sleep(5).await;
+                error_message.delete(ctx).await?;
+                return Ok(());
+            }
+        },
+        None => ctx.data().region(ctx.guild_id().map(|id| id.0)),
+    };
+    let color = ctx_util::color(ctx).await;
+    let film = match pick_film(ctx, &title, year).await? {
+        Picked::One(film) | Picked::Chosen(film) => film,
+        Picked::NotFound => {
+            let error_message = ctx.say(format!("Couldn't find `{}` film.", title)).await?;
+            sleep(5).await;
+            error_message.delete(ctx).await?;
+            return Ok(());
+        }
+        Picked::Cancelled => return Ok(()),
+    };
+    let cache = &ctx.data().watch_cache;
+    let key = keys::tmdb_id(film.id);
+    let mut providers = match cache.get(&key) {
+        Some(providers) => providers,
+        None => {
+            let providers = ctx.data().tmdb.watch_providers(film.id).await?;
+            cache.insert(&key, providers.clone());
+            providers
+        }
+    };
+    let Some(providers) = providers.remove(&region) else {
+        let error_message = ctx
+            .say(format!(
+                "`{}` can't be streamed, rented or bought in {}.",
+                film.display_title(),
+                region
+            ))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    };
+    let embeds = render::watch_offers(&film.display_title(), &region, &providers, color);
+    ctx.send(|m| {
+        m.embeds = embeds;
+        m
+    })
+    .await?;
+    Ok(())
+}
+
+/// Set the country films are looked up for in this server.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn region(
+    ctx: Context<'_>,
+    #[description = "A two-letter country code like `GB`."] code: String,
+) -> Result<(), Error> {
+    let Some(region) = tmdb_util::region(&code) else {
+        let error_message = ctx
+            .say(format!("`{}` isn't a two-letter country code.", code))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    };
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    ctx.data().guilds.update(&guild_id, |settings| {
+        settings.region = Some(region.clone());
+    });
+    ctx.say(format!(
+        "Certifications and where to watch films are now looked up for {}.",
+        region
+    ))
+    .await?;
+    Ok(())
+}
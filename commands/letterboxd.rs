@@ -1,11 +1,22 @@
-use crate::commands::utils::{lbxd_util, structs};
+use crate::commands::utils::{
+    autocomplete, compare, ctx_util, dates,
+    errors::ScrapeError,
+    feed, keys, lbxd_util, paginator,
+    picker::{self, Choice, Picked},
+    render,
+    structs::{
+        self, DiaryResult, Film, FilmCandidate, Follow, LinkedAccount, ListResult, RatedFilm,
+    },
+};
+use crate::config;
 
+use futures::stream::{self, StreamExt};
 use poise::serenity_prelude as serenity;
+use rand::Rng;
 use serenity::{
     model::{id::EmojiId, misc::EmojiIdentifier},
     ReactionType::Unicode,
 };
-use tokio::{runtime::Handle, task::spawn_blocking};
 
 type Context<'a> = poise::Context<'a, structs::Data, Error>;
 type Error = Box<dyn std::error::Error + Send + Sync>;
@@ -14,11 +25,254 @@ async fn sleep(secs: u64) {
     tokio::time::sleep(tokio::time::Duration::from_secs(secs)).await;
 }
 
+/// Tells the user why a scrape failed instead of leaving the interaction hanging.
+async fn scrape_error(ctx: Context<'_>, why: ScrapeError, not_found: String) -> Result<(), Error> {
+    let (title, description) = match &why {
+        ScrapeError::NotFound => ("Not found", not_found),
+        ScrapeError::Private => (
+            "Private",
+            "That page is private, so I can't look at it.".to_string(),
+        ),
+        ScrapeError::RateLimited => (
+            "Slow down",
+            "Letterboxd is rate limiting me, please try again in a minute.".to_string(),
+        ),
+        ScrapeError::Http(_) => (
+            "Letterboxd is unreachable",
+            "I couldn't reach Letterboxd, please try again later.".to_string(),
+        ),
+        ScrapeError::LayoutChanged { .. } => (
+            "Letterboxd changed",
+            "Letterboxd's page layout changed, so I can't read it until I'm updated. \
+             This has been logged."
+                .to_string(),
+        ),
+        ScrapeError::Parse(_) => (
+            "Something broke",
+            "I couldn't make sense of what Letterboxd sent, this has been logged.".to_string(),
+        ),
+    };
+    if matches!(
+        why,
+        ScrapeError::LayoutChanged { .. } | ScrapeError::Parse(_) | ScrapeError::Http(_)
+    ) {
+        eprintln!("/{}: {}", ctx.command().qualified_name, why);
+    }
+    let error_message = ctx
+        .send(|m| {
+            m.embed(|e| {
+                e.title(title)
+                    .description(description)
+                    .color(serenity::Colour::RED)
+            })
+        })
+        .await?;
+    sleep(5).await;
+    error_message.delete(ctx).await?;
+    Ok(())
+}
+
+/// Settles on one search result, asking the author when several films share the title.
+async fn pick_film(
+    ctx: Context<'_>,
+    title: &str,
+    year: Option<i32>,
+    candidates: Vec<FilmCandidate>,
+) -> Result<Picked<String>, Error> {
+    if let Some(year) = year {
+        return Ok(match candidates.into_iter().find(|c| c.year == Some(year)) {
+            Some(candidate) => Picked::One(candidate.path),
+            None => Picked::NotFound,
+        });
+    }
+    if !picker::is_ambiguous(title, candidates.iter().map(|c| c.title.as_str())) {
+        return Ok(match candidates.into_iter().next() {
+            Some(candidate) => Picked::One(candidate.path),
+            None => Picked::NotFound,
+        });
+    }
+    let choices = candidates
+        .iter()
+        .take(picker::MAX_CHOICES)
+        .map(|c| Choice {
+            label: c.display_title(),
+            description: if c.directors.is_empty() {
+                String::new()
+            } else {
+                format!("Directed by {}", c.directors.join(", "))
+            },
+        })
+        .collect::<Vec<_>>();
+    let prompt = format!("There are a few films called `{}`, which one?", title);
+    Ok(match picker::pick(ctx, &prompt, &choices).await? {
+        Some(i) if i < candidates.len() => Picked::Chosen(candidates[i].path.clone()),
+        _ => Picked::Cancelled,
+    })
+}
+
+/// A short random token to put in a Letterboxd bio, e.g. `filmbro-k3x9qz`.
+fn verification_token() -> String {
+    let mut rng = rand::thread_rng();
+    let chars = "0123456789abcdefghijklmnopqrstuvwxyz".chars().collect::<Vec<_>>();
+    format!(
+        "filmbro-{}",
+        (0..6)
+            .map(|_| chars[rng.gen_range(0..chars.len())])
+            .collect::<String>()
+    )
+}
+
+fn user_not_found(username: &str) -> String {
+    format!(
+        "Couldn't find `{}` user.\nMake sure to provide your Letterboxd **username**, not the link.",
+        username
+    )
+}
+
+/// Picks whose account to look up: `username` if given, otherwise the account linked by
+/// `user` or the author. Tells the author and returns `None` when there's none.
+async fn resolve_username(
+    ctx: Context<'_>,
+    username: Option<String>,
+    user: Option<serenity::Member>,
+) -> Result<Option<String>, Error> {
+    if let Some(username) = username {
+        return Ok(Some(username));
+    }
+    let target = user.map(|member| member.user);
+    let target = target.as_ref().unwrap_or_else(|| ctx.author());
+    if let Some(link) = ctx.data().links.get(&target.id.to_string()) {
+        return Ok(Some(link.username));
+    }
+    let message = if target.id == ctx.author().id {
+        "You haven't linked your Letterboxd account, use `/letterboxd link` or give a username."
+            .to_string()
+    } else {
+        format!("`{}` hasn't linked their Letterboxd account.", target.name)
+    };
+    let error_message = ctx.say(message).await?;
+    sleep(5).await;
+    error_message.delete(ctx).await?;
+    Ok(None)
+}
+
+/// What to keep of a diary when browsing it.
+struct DiaryFilter {
+    year: Option<i32>,
+    month: Option<u32>,
+    min_rating: Option<f32>,
+    liked: bool,
+    rewatched: bool,
+}
+
+impl DiaryFilter {
+    fn is_empty(&self) -> bool {
+        self.year.is_none()
+            && self.month.is_none()
+            && self.min_rating.is_none()
+            && !self.liked
+            && !self.rewatched
+    }
+
+    /// The `/for/<period>/` part of diary URLs, Letterboxd filters by date on its own.
+    fn period(&self) -> Option<String> {
+        match (self.year, self.month) {
+            (Some(year), Some(month)) => Some(format!("{}/{:02}", year, month)),
+            (Some(year), None) => Some(year.to_string()),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, entry: &DiaryResult) -> bool {
+        let rated = match self.min_rating {
+            Some(min_rating) => entry.stars.is_some_and(|stars| stars >= min_rating),
+            None => true,
+        };
+        rated && (!self.liked || entry.liked) && (!self.rewatched || entry.rewatched)
+    }
+}
+
+/// Pages through the diary entries matching `filter`, going through several diary pages.
+async fn browse_diary(
+    ctx: Context<'_>,
+    username: &str,
+    color: serenity::Colour,
+    filter: DiaryFilter,
+) -> Result<(), Error> {
+    let lbxd = &ctx.data().lbxd;
+    let cache = &ctx.data().diary_page_cache;
+    let key = keys::username(username);
+    let period = filter.period();
+    let (mut avatar, mut display_name, mut entries) = (String::new(), String::new(), vec![]);
+    let mut truncated = false;
+    for page in 1..=config::DIARY_MAX_PAGES {
+        let page_key = keys::diary_page(&key, period.as_deref(), page);
+        let diary_page = match cache.get(&page_key) {
+            Some(diary_page) => diary_page,
+            None => match lbxd.get_diary_page(&key, period.as_deref(), page).await {
+                Ok(diary_page) => {
+                    cache.insert(&page_key, diary_page.clone());
+                    diary_page
+                }
+                Err(why) => return scrape_error(ctx, why, user_not_found(username)).await,
+            },
+        };
+        avatar = diary_page.avatar;
+        display_name = diary_page.display_name;
+        entries.extend(diary_page.entries.into_iter().filter(|e| filter.matches(e)));
+        if !diary_page.has_next {
+            break;
+        }
+        truncated = page == config::DIARY_MAX_PAGES;
+    }
+    if entries.is_empty() {
+        let error_message = ctx
+            .say(format!("`{}` doesn't have any matching diary entries.", username))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    ctx.data().usernames.insert(&key, key.clone());
+
+    let mut title = format!("{}'s Diary", display_name);
+    let mut url = format!("{}/{}/films/diary/", lbxd.base_url(), key);
+    if let Some(period) = &period {
+        title.push_str(&format!(" ({})", period));
+        url.push_str(&format!("for/{}/", period));
+    }
+    let pages = entries
+        .chunks(config::DIARY_ENTRIES_PER_PAGE)
+        .map(|chunk| {
+            let mut footer = render::diary_counts(chunk);
+            if truncated {
+                footer.push_str(&format!(
+                    " • latest {} entries only",
+                    config::DIARY_MAX_PAGES * config::DIARY_PAGE_ENTRIES
+                ));
+            }
+            paginator::Page {
+                title: title.clone(),
+                url: Some(url.clone()),
+                description: Some(render::diary_entries(chunk)),
+                thumbnail: (!avatar.is_empty()).then(|| avatar.clone()),
+                footer: Some(footer),
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+    paginator::start(ctx, color, *ctx.author().id.as_u64(), &pages).await?;
+    Ok(())
+}
+
 /// Base Letterboxd commands.
 #[poise::command(
     slash_command,
     rename = "letterboxd",
-    subcommands("diary", "film", "profile", "roulette")
+    subcommands(
+        "common", "compare", "diary", "film", "follow", "link", "list", "profile", "review",
+        "roulette", "unfollow", "unfurl", "unlink", "verify", "watchlist"
+    )
 )]
 pub async fn base(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
@@ -28,86 +282,99 @@ pub async fn base(_ctx: Context<'_>) -> Result<(), Error> {
 #[poise::command(slash_command)]
 pub async fn diary(
     ctx: Context<'_>,
-    #[description = "The profile username."] username: String,
+    #[description = "The profile username, defaults to your linked account."]
+    #[autocomplete = "autocomplete::username"]
+    username: Option<String>,
+    #[description = "A member whose linked account to look up."] user: Option<serenity::Member>,
+    #[description = "Page through the whole diary instead of the latest entries."]
+    all: Option<bool>,
+    #[description = "Only entries watched that year."]
+    #[min = 1900]
+    year: Option<i32>,
+    #[description = "Only entries watched that month of the year."]
+    #[min = 1]
+    #[max = 12]
+    month: Option<u32>,
+    #[description = "Only entries rated at least this many stars."]
+    #[min = 0.5]
+    #[max = 5]
+    min_rating: Option<f32>,
+    #[description = "Only liked entries."] liked: Option<bool>,
+    #[description = "Only rewatches."] rewatched: Option<bool>,
 ) -> Result<(), Error> {
     ctx.defer().await?;
-    let color = ctx
-        .author_member()
-        .await
-        .unwrap()
-        .colour(&ctx.serenity_context().cache)
-        .unwrap();
-    let cache = &ctx.data().diary_cache;
-    let diaries = if cache.read().unwrap().contains_key(&username) {
-        cache.read().unwrap().get(&username).cloned().unwrap()
-    } else {
-        let username_clone = username.clone();
-        let handle = Handle::current();
-        let diaries = spawn_blocking(move || lbxd_util::get_diary(username_clone).unwrap())
-            .await
-            .unwrap();
-        drop(handle);
-        ctx.data()
-            .diary_cache
-            .write()
-            .unwrap()
-            .insert(username.clone(), diaries);
-        ctx.data()
-            .diary_cache
-            .read()
-            .unwrap()
-            .get(&username)
-            .cloned()
-            .unwrap()
-    };
-    if !diaries.0.is_empty() {
-        let mut description = String::new();
-        for diary in diaries.2 {
-            let rewatched = if diary.rewatched {
-                "🔄 ".to_string()
-            } else {
-                String::new()
-            };
-            let liked = if diary.liked {
-                "❤️ ".to_string()
-            } else {
-                String::new()
-            };
-            let reviewed = if diary.reviewed {
-                "💬".to_string()
-            } else {
-                String::new()
-            };
-            let rating = if !diary.rating.is_empty() {
-                format!("{} ", diary.rating)
-            } else {
-                String::new()
-            };
-            description.push_str(
-                format!(
-                    "[**{}**]({})\n{} {}{}{}{}\n",
-                    diary.title, diary.url, diary.date, rating, rewatched, liked, reviewed,
-                )
-                .as_str(),
-            )
-        }
-        ctx.send(|m| {
-            m.embed(|e| {
-                e.title(format!("{}'s Recent Diary Entries", diaries.1))
-                    .description(description)
-                    .url(format!("https://letterboxd.com/{}/films/diary", username))
-                    .color(color)
-                    .thumbnail(diaries.0)
-            })
-        })
-        .await?;
-    } else if diaries.2[0].title.is_empty() {
-        let error_message = ctx.say(format!(
-            "Couldn't find `{}` user.\nMake sure to provide your Letterboxd **username**, not the link.",
-            username
-        )).await?;
+    let Some(username) = resolve_username(ctx, username, user).await? else {
+        return Ok(());
+    };
+    let color = ctx_util::color(ctx).await;
+    let filter = DiaryFilter {
+        year,
+        month,
+        min_rating,
+        liked: liked.unwrap_or(false),
+        rewatched: rewatched.unwrap_or(false),
+    };
+    if month.is_some() && year.is_none() {
+        let error_message = ctx.say("Pick a year to go with the month.").await?;
         sleep(5).await;
         error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    if let Some(year) = year.filter(|year| *year > dates::Date::today().year) {
+        let error_message = ctx
+            .say(format!("Nobody has logged anything in {} yet.", year))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    if all.unwrap_or(false) || !filter.is_empty() {
+        return browse_diary(ctx, &username, color, filter).await;
+    }
+    let key = keys::username(&username);
+    let diaries = match ctx.data().diary(&key).await {
+        Ok(diaries) => diaries,
+        Err(why) => return scrape_error(ctx, why, user_not_found(&username)).await,
+    };
+    if !diaries.2.is_empty() {
+        let description = render::diary_entries(&diaries.2);
+        let reviewed = diaries.2.iter().filter(|d| d.reviewed).collect::<Vec<_>>();
+        let reply = ctx
+            .send(|m| {
+                m.embed(|e| {
+                    if let Some(avatar) = &diaries.0 {
+                        e.thumbnail(avatar);
+                    }
+                    e.title(format!("{}'s Recent Diary Entries", diaries.1))
+                        .description(description)
+                        .url(format!(
+                            "{}/{}/films/diary",
+                            ctx.data().lbxd.base_url(),
+                            key
+                        ))
+                        .color(color)
+                });
+                if !reviewed.is_empty() {
+                    m.components(|c| {
+                        c.create_action_row(|ar| {
+                            for (i, diary) in reviewed.iter().enumerate() {
+                                ar.create_button(|b| {
+                                    b.custom_id(format!("{}review{}", ctx.id(), i))
+                                        .style(serenity::ButtonStyle::Secondary)
+                                        .label(render::truncate(&diary.title, 70))
+                                        .emoji(Unicode("💬".to_string()))
+                                });
+                            }
+                            ar
+                        })
+                    });
+                }
+                m
+            })
+            .await?;
+        if !reviewed.is_empty() {
+            read_reviews(ctx, reply, &key, &reviewed, color).await?;
+        }
     } else {
         let error_message = ctx
             .say(format!("`{}` doesn't have any recent diaries.", username))
@@ -118,259 +385,1063 @@ pub async fn diary(
     Ok(())
 }
 
+/// Answers the review buttons of a diary embed until they time out.
+///
+/// Anyone can press them, the review is only shown to whoever did.
+async fn read_reviews(
+    ctx: Context<'_>,
+    reply: poise::ReplyHandle<'_>,
+    username: &str,
+    entries: &[&DiaryResult],
+    color: serenity::Colour,
+) -> Result<(), Error> {
+    let prefix = format!("{}review", ctx.id());
+    let filter_prefix = prefix.clone();
+    while let Some(press) = serenity::CollectComponentInteraction::new(ctx)
+        .filter(move |press| press.data.custom_id.starts_with(&filter_prefix))
+        .timeout(config::REVIEW_BUTTON_TIMEOUT)
+        .await
+    {
+        let Some(entry) = press.data.custom_id[prefix.len()..]
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| entries.get(i))
+        else {
+            continue;
+        };
+        // Scraping might take longer than Discord waits for an answer.
+        press
+            .create_interaction_response(ctx, |r| {
+                r.kind(serenity::InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|d| d.ephemeral(true))
+            })
+            .await?;
+        let review = match lbxd_util::film_path_from_url(&entry.url) {
+            Some(path) => ctx.data().review(username, &path).await,
+            None => Err(ScrapeError::NotFound),
+        };
+        press
+            .create_followup_message(ctx, |f| {
+                f.ephemeral(true);
+                match &review {
+                    Ok(review) => f.embed(|e| render::review(e, review, color)),
+                    Err(why) => f.content(format!("Couldn't get that review: {}.", why)),
+                }
+            })
+            .await?;
+    }
+    reply.edit(ctx, |m| m.components(|c| c)).await?;
+    Ok(())
+}
+
 /// Get a film information based off Letterboxd.
 #[poise::command(slash_command)]
 pub async fn film(
     ctx: Context<'_>,
-    #[description = "The film title."] title: String,
+    #[description = "The film title or its Letterboxd link."]
+    #[autocomplete = "autocomplete::film_title"]
+    title: String,
+    #[description = "The release year of the film."]
+    #[min = 1900]
+    year: Option<i32>,
 ) -> Result<(), Error> {
     ctx.defer().await?;
+    let (title, year) = autocomplete::split_year(&title, year);
+    if ctx_util::reject_future_year(ctx, year).await? {
+        return Ok(());
+    }
+    let lbxd = &ctx.data().lbxd;
     let cache = &ctx.data().film_cache;
-    let film_info = if cache.read().unwrap().contains_key(&title) {
-        cache.read().unwrap().get(&title).cloned().unwrap()
+    let not_found = format!("Couldn't find `{}` film.", title);
+    let key = keys::title(&title, year);
+    let film_info = match cache.get(&key) {
+        Some(film_info) => film_info,
+        None => {
+            let picked = match lbxd_util::film_path_from_url(&title) {
+                Some(path) => Picked::One(path),
+                None => match lbxd.search_films(&title).await {
+                    Ok(candidates) => pick_film(ctx, &title, year, candidates).await?,
+                    Err(why) => return scrape_error(ctx, why, not_found).await,
+                },
+            };
+            // Only remember unambiguous lookups, otherwise the next person asking for the
+            // same title would silently get whatever the last person picked.
+            let (path, remember) = match picked {
+                Picked::One(path) => (path, true),
+                Picked::Chosen(path) => (path, false),
+                Picked::NotFound => {
+                    return scrape_error(ctx, ScrapeError::NotFound, not_found).await
+                }
+                Picked::Cancelled => return Ok(()),
+            };
+            let film_info = match ctx.data().film_at(&path).await {
+                Ok(film_info) => film_info,
+                Err(why) => return scrape_error(ctx, why, not_found).await,
+            };
+            if remember {
+                cache.alias(key, keys::film_slug(&film_info.slug));
+            }
+            film_info
+        }
+    };
+    let color = ctx_util::color(ctx).await;
+    ctx.send(|m| m.embed(|e| render::film(e, &film_info, color)))
+        .await?;
+    Ok(())
+}
+
+/// Post a member's new diary entries into a channel.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn follow(
+    ctx: Context<'_>,
+    #[description = "The profile username."]
+    #[autocomplete = "autocomplete::username"]
+    username: String,
+    #[description = "Where to post the entries, defaults to this channel."]
+    #[channel_types("Text", "News")]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let channel_id = channel.map(|c| c.id).unwrap_or_else(|| ctx.channel_id());
+    let key = keys::username(&username);
+    let guilds = &ctx.data().guilds;
+    let follows = guilds.get(&guild_id).unwrap_or_default().follows;
+    if follows.len() >= config::FEED_MAX_FOLLOWS && !follows.iter().any(|f| f.username == key) {
+        let error_message = ctx
+            .say(format!(
+                "This server already follows {} diaries, unfollow some first.",
+                config::FEED_MAX_FOLLOWS
+            ))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    // Only what gets logged from now on is posted, not the whole recent diary.
+    let diary = match ctx.data().lbxd.get_diary(&key).await {
+        Ok(diary) => diary,
+        Err(why) => return scrape_error(ctx, why, user_not_found(&username)).await,
+    };
+    let mut seen = vec![];
+    feed::mark_seen(&mut seen, diary.2.iter().map(|entry| entry.feed_key()));
+    ctx.data().usernames.insert(&key, key.clone());
+    guilds.update(&guild_id, |settings| {
+        settings.follows.retain(|f| f.username != key);
+        settings.follows.push(Follow {
+            username: key.clone(),
+            channel_id: *channel_id.as_u64(),
+            seen,
+        });
+    });
+    ctx.say(format!(
+        "Posting `{}`'s new diary entries in <#{}>.",
+        key, channel_id
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Stop posting a member's new diary entries.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn unfollow(
+    ctx: Context<'_>,
+    #[description = "The profile username."]
+    #[autocomplete = "autocomplete::username"]
+    username: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    let key = keys::username(&username);
+    let removed = ctx.data().guilds.update(&guild_id, |settings| {
+        let before = settings.follows.len();
+        settings.follows.retain(|f| f.username != key);
+        settings.follows.len() != before
+    });
+    let message = if removed {
+        format!("Stopped posting `{}`'s diary entries.", key)
     } else {
-        let title_clone = title.clone();
-        let handle = Handle::current();
-        let film_info = spawn_blocking(move || lbxd_util::get_film(&title_clone).unwrap())
-            .await
-            .unwrap();
-        drop(handle);
-        ctx.data()
-            .film_cache
-            .write()
-            .unwrap()
-            .insert(title.clone(), film_info);
-        ctx.data()
-            .film_cache
-            .read()
-            .unwrap()
-            .get(&title)
-            .cloned()
-            .unwrap()
-    };
-    if film_info.found {
-        let color = ctx
-            .author_member()
-            .await
-            .unwrap()
-            .colour(&ctx.serenity_context().cache)
-            .unwrap();
-        let tagline = if !film_info.tagline.is_empty() {
-            format!("**{}**\n", film_info.tagline)
-        } else {
-            String::new()
-        };
-        let rating = if film_info.rating != *" 0.0" {
-            format!("{}\n", film_info.rating)
-        } else {
-            "".to_string()
-        };
-        let plural_check = vec!["", "s"][(film_info.directors.split(", ").count() > 1) as usize];
-        let country_check = if !film_info.countries.is_empty() {
-            "|"
-        } else {
-            ""
+        format!("This server doesn't follow `{}`.", key)
+    };
+    ctx.say(message).await?;
+    Ok(())
+}
+
+/// Answer Letterboxd links posted in this server with embeds.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn unfurl(
+    ctx: Context<'_>,
+    #[description = "Whether links get answered."] enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().unwrap().to_string();
+    ctx.data().guilds.update(&guild_id, |settings| {
+        settings.unfurl = enabled;
+    });
+    let message = if enabled {
+        "Letterboxd links posted in this server will be answered with embeds."
+    } else {
+        "Letterboxd links posted in this server won't be answered anymore."
+    };
+    ctx.say(message).await?;
+    Ok(())
+}
+
+/// Link your Discord account to your Letterboxd account.
+#[poise::command(slash_command)]
+pub async fn link(
+    ctx: Context<'_>,
+    #[description = "Your Letterboxd username."] username: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let user = match ctx.data().lbxd.get_profile(&keys::username(&username)).await {
+        Ok(user) => user,
+        Err(why) => return scrape_error(ctx, why, user_not_found(&username)).await,
+    };
+    let canonical = keys::username(&user.username);
+    let links = &ctx.data().links;
+    let author_id = ctx.author().id.to_string();
+    let claimed = links
+        .entries()
+        .into_iter()
+        .any(|(id, link)| id != author_id && link.verified && link.username == canonical);
+    if claimed {
+        let error_message = ctx
+            .say(format!(
+                "`{}` is already linked to someone who verified owning it.",
+                user.username
+            ))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    ctx.data().profile_cache.insert(&canonical, user.clone());
+    ctx.data().usernames.insert(&canonical, canonical.clone());
+    if let Some(link) = links.get(&author_id) {
+        if link.verified && link.username == canonical {
+            ctx.say(format!("You're already linked to `{}`.", user.username))
+                .await?;
+            return Ok(());
+        }
+    }
+    let token = verification_token();
+    links.insert(
+        author_id,
+        LinkedAccount {
+            username: canonical,
+            verified: false,
+            token: token.clone(),
+        },
+    );
+    ctx.say(format!(
+        "Linked your account to `{}`.\nTo prove it's yours, put `{}` anywhere in your \
+         [Letterboxd bio](https://letterboxd.com/settings/) and run `/letterboxd verify`.",
+        user.username, token
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Verify you own your linked Letterboxd account.
+#[poise::command(slash_command)]
+pub async fn verify(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+    let links = &ctx.data().links;
+    let author_id = ctx.author().id.to_string();
+    let mut link = match links.get(&author_id) {
+        Some(link) => link,
+        None => {
+            let error_message = ctx
+                .say("You haven't linked a Letterboxd account, use `/letterboxd link` first.")
+                .await?;
+            sleep(5).await;
+            error_message.delete(ctx).await?;
+            return Ok(());
+        }
+    };
+    if link.verified {
+        ctx.say(format!("`{}` is already verified.", link.username))
+            .await?;
+        return Ok(());
+    }
+    // Skip the cache, the bio was most likely edited a moment ago.
+    let user = match ctx.data().lbxd.get_profile(&link.username).await {
+        Ok(user) => user,
+        Err(why) => return scrape_error(ctx, why, user_not_found(&link.username)).await,
+    };
+    ctx.data().profile_cache.insert(&link.username, user.clone());
+    if !user.bio.contains(&link.token) {
+        let error_message = ctx
+            .say(format!(
+                "Couldn't find `{}` in `{}`'s bio, make sure the bio is saved and try again.",
+                link.token, user.username
+            ))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    link.verified = true;
+    links.insert(author_id, link);
+    ctx.say(format!(
+        "Verified `{}`, you can take the token out of your bio now.",
+        user.username
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Unlink your Discord account from your Letterboxd account.
+#[poise::command(slash_command)]
+pub async fn unlink(ctx: Context<'_>) -> Result<(), Error> {
+    let message = match ctx.data().links.remove(&ctx.author().id.to_string()) {
+        Some(link) => format!("Unlinked your account from `{}`.", link.username),
+        None => "You haven't linked a Letterboxd account.".to_string(),
+    };
+    ctx.say(message).await?;
+    Ok(())
+}
+
+/// Gets the list `list`, given as a name, a slug or a link, going through the cache. Tells
+/// the author and returns `None` when it can't.
+async fn resolve_list(
+    ctx: Context<'_>,
+    list: &str,
+    username: Option<String>,
+    user: Option<serenity::Member>,
+) -> Result<Option<ListResult>, Error> {
+    let (username, slug) = match lbxd_util::list_path_from_url(list) {
+        Some(path) => path,
+        None => match resolve_username(ctx, username, user).await? {
+            Some(username) => (username, keys::list_slug(list)),
+            None => return Ok(None),
+        },
+    };
+    let list = match ctx.data().list(&username, &slug).await {
+        Ok(list) => list,
+        Err(why) => {
+            let not_found = format!("Couldn't find `{}`'s list `{}`.", username, slug);
+            scrape_error(ctx, why, not_found).await?;
+            return Ok(None);
+        }
+    };
+    if list.entries.is_empty() {
+        let error_message = ctx.say(format!("`{}` is empty.", list.title)).await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(None);
+    }
+    Ok(Some(list))
+}
+
+fn film_count(count: usize) -> String {
+    format!("{} film{}", count, ["", "s"][(count != 1) as usize])
+}
+
+/// Pages through list entries, `description` only being shown on the first page.
+async fn browse_entries(
+    ctx: Context<'_>,
+    title: &str,
+    url: Option<&str>,
+    description: Option<&str>,
+    entries: &[structs::ListEntry],
+    footer: &str,
+    color: serenity::Colour,
+) -> Result<(), Error> {
+    let base_url = ctx.data().lbxd.base_url();
+    let pages = entries
+        .chunks(config::LIST_ENTRIES_PER_PAGE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut page_description = String::new();
+            if let (0, Some(description)) = (i, description) {
+                page_description.push_str(&format!("{}\n\n", render::truncate(description, 1000)));
+            }
+            page_description.push_str(&render::list_entries(chunk, base_url));
+            paginator::Page {
+                title: title.to_string(),
+                url: url.map(str::to_string),
+                description: Some(page_description),
+                thumbnail: chunk.iter().find_map(|entry| entry.poster.clone()),
+                footer: Some(footer.to_string()),
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+    paginator::start(ctx, color, *ctx.author().id.as_u64(), &pages).await?;
+    Ok(())
+}
+
+/// What to keep of a list when filtering it by film details.
+struct FilmFilter {
+    /// In minutes.
+    max_runtime: Option<u32>,
+    genre: Option<String>,
+}
+
+impl FilmFilter {
+    fn is_empty(&self) -> bool {
+        self.max_runtime.is_none() && self.genre.is_none()
+    }
+
+    fn matches(&self, film: &Film) -> bool {
+        let short = match self.max_runtime {
+            Some(max_runtime) => film.runtime.is_some_and(|runtime| runtime <= max_runtime),
+            None => true,
         };
-        let duration = if film_info.duration != *"0m" {
-            format!("{}\n", film_info.duration)
-        } else {
-            "".to_string()
+        let genre = match &self.genre {
+            Some(genre) => film
+                .genres
+                .iter()
+                .any(|g| g.eq_ignore_ascii_case(genre.trim())),
+            None => true,
         };
-        ctx.send(|m| {
-            m.embed(|e| {
-                e.title(film_info.title)
-                    .description(format!(
-                        "{}{}\n\n{}Director{}: {}\n{} {} {}\n{}\u{1f440} {} | ❤️ {} | \u{1f4ac} {}",
-                        tagline,
-                        film_info.synopsis,
-                        rating,
-                        plural_check,
-                        film_info.directors,
-                        film_info.countries,
-                        country_check,
-                        film_info.genre,
-                        duration,
-                        film_info.info["people"],
-                        film_info.info["likes"],
-                        film_info.info.get("reviews").unwrap_or(&"0".to_string())
-                    ))
-                    .url(film_info.url)
-                    .color(color)
-                    .thumbnail(film_info.poster)
-            })
+        short && genre
+    }
+
+    /// E.g. `2h at most, Horror`, for footers.
+    fn describe(&self) -> String {
+        let mut parts = vec![];
+        if let Some(max_runtime) = self.max_runtime {
+            parts.push(format!("{} at most", render::runtime(max_runtime)));
+        }
+        if let Some(genre) = &self.genre {
+            parts.push(genre.trim().to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// Keeps the entries whose film matches `filter`, filling in their year and poster. Only
+/// the first [`config::FILM_FILTER_MAX_LOOKUPS`] entries are looked up, stopping once
+/// [`config::FILM_FILTER_MAX_MATCHES`] match, the second value being how many were checked
+/// if that wasn't all of them.
+async fn filter_entries(
+    ctx: Context<'_>,
+    entries: Vec<structs::ListEntry>,
+    filter: &FilmFilter,
+) -> Result<(Vec<structs::ListEntry>, Option<usize>), ScrapeError> {
+    if filter.is_empty() {
+        return Ok((entries, None));
+    }
+    let total = entries.len();
+    // In order, so stopping early keeps the first matches of the list.
+    let mut films = stream::iter(entries.into_iter().take(config::FILM_FILTER_MAX_LOOKUPS))
+        .map(move |entry| async move {
+            let film = ctx.data().film_at(&entry.path).await;
+            (entry, film)
         })
-        .await?;
-    } else {
-        let error_message = ctx.say(format!("Couldn't find `{}` film.", title)).await?;
+        .buffered(config::FILM_FILTER_CONCURRENCY);
+    let mut kept = vec![];
+    let mut checked = 0;
+    while let Some((mut entry, film)) = films.next().await {
+        checked += 1;
+        let film = match film {
+            Ok(film) => film,
+            Err(ScrapeError::RateLimited) => return Err(ScrapeError::RateLimited),
+            // One broken film page shouldn't spoil the whole list.
+            Err(why) => {
+                eprintln!("Couldn't filter `{}`: {}", entry.path, why);
+                continue;
+            }
+        };
+        if filter.matches(&film) {
+            entry.year = film.year;
+            entry.poster = entry.poster.or(film.poster);
+            kept.push(entry);
+            if kept.len() == config::FILM_FILTER_MAX_MATCHES {
+                break;
+            }
+        }
+    }
+    Ok((kept, (checked < total).then_some(checked)))
+}
+
+/// The footer of filtered entries, e.g. `3 films • 2h at most • first 50 checked`.
+fn filtered_footer(count: usize, filter: &FilmFilter, checked: Option<usize>) -> String {
+    let mut footer = film_count(count);
+    if !filter.is_empty() {
+        footer.push_str(&format!(" • {}", filter.describe()));
+    }
+    if let Some(checked) = checked {
+        footer.push_str(&format!(" • first {} checked", checked));
+    }
+    footer
+}
+
+/// Gets `username`'s watchlist, going through the cache.
+async fn get_watchlist(ctx: Context<'_>, username: &str) -> Result<ListResult, ScrapeError> {
+    let cache = &ctx.data().watchlist_cache;
+    let key = keys::username(username);
+    if let Some(watchlist) = cache.get(&key) {
+        return Ok(watchlist);
+    }
+    let watchlist = ctx.data().lbxd.get_watchlist(&key).await?;
+    cache.insert(&key, watchlist.clone());
+    ctx.data().usernames.insert(&key, key.clone());
+    Ok(watchlist)
+}
+
+/// Get the films on a member's watchlist.
+#[poise::command(slash_command)]
+pub async fn watchlist(
+    ctx: Context<'_>,
+    #[description = "The profile username, defaults to your linked account."]
+    #[autocomplete = "autocomplete::username"]
+    username: Option<String>,
+    #[description = "A member whose linked account to look up."] user: Option<serenity::Member>,
+    #[description = "Only films at most this many minutes long."]
+    #[min = 1]
+    max_runtime: Option<u32>,
+    #[description = "Only films of this genre, e.g. Horror."] genre: Option<String>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let Some(username) = resolve_username(ctx, username, user).await? else {
+        return Ok(());
+    };
+    let watchlist = match get_watchlist(ctx, &username).await {
+        Ok(watchlist) => watchlist,
+        Err(why) => return scrape_error(ctx, why, user_not_found(&username)).await,
+    };
+    let filter = FilmFilter { max_runtime, genre };
+    let (entries, checked) = match filter_entries(ctx, watchlist.entries, &filter).await {
+        Ok(filtered) => filtered,
+        Err(why) => return scrape_error(ctx, why, user_not_found(&username)).await,
+    };
+    if entries.is_empty() {
+        let error_message = ctx
+            .say(format!(
+                "`{}` doesn't have any matching films on their watchlist.",
+                username
+            ))
+            .await?;
         sleep(5).await;
         error_message.delete(ctx).await?;
+        return Ok(());
     }
-    Ok(())
+    let color = ctx_util::color(ctx).await;
+    let mut footer = filtered_footer(entries.len(), &filter, checked);
+    if watchlist.truncated {
+        footer.push_str(" • more on Letterboxd");
+    }
+    browse_entries(
+        ctx,
+        &watchlist.title,
+        Some(&watchlist.url),
+        None,
+        &entries,
+        &footer,
+        color,
+    )
+    .await
 }
 
-/// Get a Letterboxd profile information.
+/// Find the films on everyone's watchlists.
 #[poise::command(slash_command)]
-pub async fn profile(
+pub async fn common(
     ctx: Context<'_>,
-    #[description = "The profile username."] username: String,
+    #[description = "A profile username."]
+    #[autocomplete = "autocomplete::username"]
+    user1: String,
+    #[description = "Another profile username."]
+    #[autocomplete = "autocomplete::username"]
+    user2: String,
+    #[description = "Another profile username."]
+    #[autocomplete = "autocomplete::username"]
+    user3: Option<String>,
+    #[description = "Another profile username."]
+    #[autocomplete = "autocomplete::username"]
+    user4: Option<String>,
+    #[description = "Another profile username."]
+    #[autocomplete = "autocomplete::username"]
+    user5: Option<String>,
+    #[description = "Only films at most this many minutes long."]
+    #[min = 1]
+    max_runtime: Option<u32>,
+    #[description = "Only films of this genre, e.g. Horror."] genre: Option<String>,
 ) -> Result<(), Error> {
     ctx.defer().await?;
-    let cache = &ctx.data().profile_cache;
-    let user = if cache.read().unwrap().contains_key(&username) {
-        cache.read().unwrap().get(&username).cloned().unwrap()
-    } else {
-        let username_clone = username.clone();
-        let handle = Handle::current();
-        let user = spawn_blocking(move || lbxd_util::get_profile(&username_clone).unwrap())
-            .await
-            .unwrap();
-        drop(handle);
-        ctx.data()
-            .profile_cache
-            .write()
-            .unwrap()
-            .insert(username.clone(), user);
-        ctx.data()
-            .profile_cache
-            .read()
-            .unwrap()
-            .get(&username)
-            .cloned()
-            .unwrap() //&ctx.data().profile_cache.get(&username).unwrap()
-    };
-    if user.found {
-        let mut description = String::new();
-        if !user.location.is_empty() || !user.bio.is_empty() {
-            description.push_str(&format!(
-                "{}\n{}\n———————————————\n",
-                user.location, user.bio
-            ));
+    let mut usernames = vec![];
+    for username in [Some(user1), Some(user2), user3, user4, user5]
+        .into_iter()
+        .flatten()
+    {
+        let key = keys::username(&username);
+        if !usernames.contains(&key) {
+            usernames.push(key);
         }
-        if !user.favorites.is_empty() {
-            description.push_str(&format!("{}\n", user.favorites));
+    }
+    if usernames.len() < 2 {
+        let error_message = ctx.say("Give at least two different usernames.").await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    let mut watchlists = vec![];
+    for username in &usernames {
+        match get_watchlist(ctx, username).await {
+            Ok(watchlist) => watchlists.push(watchlist),
+            Err(why) => return scrape_error(ctx, why, user_not_found(username)).await,
         }
-        let color = ctx
-            .author_member()
-            .await
-            .unwrap()
-            .colour(&ctx.serenity_context().cache)
-            .unwrap();
-        ctx.send(|m| {
-                if !user.websites.is_empty() {
-                    m.components(|c| {
-                        c.create_action_row(|ar| {
-                            if user.websites.len() == 1 {
-                                if !user.websites[0].contains("twitter") {
-                                    ar.create_button(|b| {
-                                    b.style(serenity::ButtonStyle::Link).label("Website").url(&user.websites[0]).emoji(Unicode("🌐".to_string()))
-                                })
-                                } else {
-                                    ar.create_button(|b| {
-                                    b.style(serenity::ButtonStyle::Link).label("Twitter").url(&user.websites[0]).emoji(EmojiIdentifier { animated: false , id: EmojiId(1083962148633456670), name: "twt".to_string()})
-                                })
-                                }
-                            } else {
-                                ar.create_button(|b| {
-                                    b.style(serenity::ButtonStyle::Link).label("Website").url(&user.websites[0]).emoji(Unicode("🌐".to_string()))
-                                });
-                                ar.create_button(|b| {
-                                    b.style(serenity::ButtonStyle::Link).label("Twitter").url(&user.websites[1]).emoji(EmojiIdentifier { animated: false , id: EmojiId(1083962148633456670), name: "twt".to_string()})
-                                })
-                            }
-                        })
-                    });
+    }
+    let filter = FilmFilter { max_runtime, genre };
+    let common = lbxd_util::common_entries(&watchlists);
+    let (entries, checked) = match filter_entries(ctx, common, &filter).await {
+        Ok(filtered) => filtered,
+        Err(why) => {
+            let not_found = "Couldn't look up the films in common.".to_string();
+            return scrape_error(ctx, why, not_found).await;
+        }
+    };
+    let names = usernames
+        .iter()
+        .map(|username| format!("`{}`", username))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if entries.is_empty() {
+        let error_message = ctx
+            .say(format!("{} don't have any matching films in common.", names))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    let color = ctx_util::color(ctx).await;
+    let mut footer = filtered_footer(entries.len(), &filter, checked);
+    if watchlists.iter().any(|watchlist| watchlist.truncated) {
+        footer.push_str(&format!(
+            " • first {} watchlist films only",
+            config::WATCHLIST_MAX_PAGES * config::WATCHLIST_PAGE_FILMS
+        ));
+    }
+    let description = format!("On the watchlists of {}.", names);
+    browse_entries(
+        ctx,
+        "Common Watchlist",
+        None,
+        Some(&description),
+        &entries,
+        &footer,
+        color,
+    )
+    .await
+}
+
+/// Gets every film `username` rated, going through the cache.
+async fn get_ratings(ctx: Context<'_>, username: &str) -> Result<Vec<RatedFilm>, ScrapeError> {
+    let cache = &ctx.data().ratings_cache;
+    let key = keys::username(username);
+    if let Some(ratings) = cache.get(&key) {
+        return Ok(ratings);
+    }
+    let ratings = ctx.data().lbxd.get_ratings(&key).await?;
+    cache.insert(&key, ratings.clone());
+    ctx.data().usernames.insert(&key, key.clone());
+    Ok(ratings)
+}
+
+/// See how alike two members rate films.
+#[poise::command(slash_command)]
+pub async fn compare(
+    ctx: Context<'_>,
+    #[description = "A profile username."]
+    #[autocomplete = "autocomplete::username"]
+    user1: String,
+    #[description = "Another profile username."]
+    #[autocomplete = "autocomplete::username"]
+    user2: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let (first, second) = (keys::username(&user1), keys::username(&user2));
+    if first == second {
+        let error_message = ctx.say("Give two different usernames.").await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    let first_ratings = match get_ratings(ctx, &first).await {
+        Ok(ratings) => ratings,
+        Err(why) => return scrape_error(ctx, why, user_not_found(&user1)).await,
+    };
+    let second_ratings = match get_ratings(ctx, &second).await {
+        Ok(ratings) => ratings,
+        Err(why) => return scrape_error(ctx, why, user_not_found(&user2)).await,
+    };
+    let comparison = compare::compare(&first_ratings, &second_ratings);
+    if comparison.overlap == 0 {
+        let error_message = ctx
+            .say(format!(
+                "`{}` and `{}` haven't rated any of the same films.",
+                first, second
+            ))
+            .await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    let color = ctx_util::color(ctx).await;
+    let base_url = ctx.data().lbxd.base_url();
+    ctx.send(|m| {
+        m.embed(|e| render::comparison(e, &first, &second, &comparison, base_url, color))
+    })
+    .await?;
+    Ok(())
+}
+
+/// Letterboxd lists.
+#[poise::command(slash_command, subcommands("list_view", "list_roulette"))]
+pub async fn list(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Page through a member's list.
+#[poise::command(slash_command, rename = "view")]
+pub async fn list_view(
+    ctx: Context<'_>,
+    #[description = "The list name or its Letterboxd link."] list: String,
+    #[description = "The author's username, defaults to your linked account."]
+    #[autocomplete = "autocomplete::username"]
+    username: Option<String>,
+    #[description = "A member whose linked account to look up."] user: Option<serenity::Member>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let Some(list) = resolve_list(ctx, &list, username, user).await? else {
+        return Ok(());
+    };
+    let color = ctx_util::color(ctx).await;
+    let mut footer = format!(
+        "List by {} • {}",
+        list.author,
+        film_count(list.entries.len())
+    );
+    if list.truncated {
+        footer.push_str(" • more on Letterboxd");
+    }
+    browse_entries(
+        ctx,
+        &list.title,
+        Some(&list.url),
+        list.description.as_deref(),
+        &list.entries,
+        &footer,
+        color,
+    )
+    .await
+}
+
+/// Get a random film off a member's list.
+#[poise::command(slash_command, rename = "roulette")]
+pub async fn list_roulette(
+    ctx: Context<'_>,
+    #[description = "The list name or its Letterboxd link."] list: String,
+    #[description = "The author's username, defaults to your linked account."]
+    #[autocomplete = "autocomplete::username"]
+    username: Option<String>,
+    #[description = "A member whose linked account to look up."] user: Option<serenity::Member>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let Some(list) = resolve_list(ctx, &list, username, user).await? else {
+        return Ok(());
+    };
+    let entry = &list.entries[rand::thread_rng().gen_range(0..list.entries.len())];
+    let film_info = match ctx.data().film_at(&entry.path).await {
+        Ok(film_info) => film_info,
+        Err(why) => {
+            let not_found = format!("Couldn't find `{}` film.", entry.display_title());
+            return scrape_error(ctx, why, not_found).await;
+        }
+    };
+    let color = ctx_util::color(ctx).await;
+    ctx.send(|m| {
+        m.embed(|e| {
+            render::film(e, &film_info, color)
+                .footer(|f| f.text(format!("Picked from {}", list.title)))
+        })
+    })
+    .await?;
+    Ok(())
+}
+
+/// Read a member's review of a film.
+#[poise::command(slash_command)]
+pub async fn review(
+    ctx: Context<'_>,
+    #[description = "The film title or its Letterboxd link."]
+    #[autocomplete = "autocomplete::film_title"]
+    film: String,
+    #[description = "The profile username, defaults to your linked account."]
+    #[autocomplete = "autocomplete::username"]
+    username: Option<String>,
+    #[description = "A member whose linked account to look up."] user: Option<serenity::Member>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let Some(username) = resolve_username(ctx, username, user).await? else {
+        return Ok(());
+    };
+    let key = keys::username(&username);
+    let (title, year) = autocomplete::split_year(&film, None);
+    let not_found = format!("Couldn't find `{}` film.", title);
+    let path = match lbxd_util::film_path_from_url(&title) {
+        Some(path) => path,
+        None => {
+            let candidates = match ctx.data().lbxd.search_films(&title).await {
+                Ok(candidates) => candidates,
+                Err(why) => return scrape_error(ctx, why, not_found).await,
+            };
+            match pick_film(ctx, &title, year, candidates).await? {
+                Picked::One(path) | Picked::Chosen(path) => path,
+                Picked::NotFound => {
+                    return scrape_error(ctx, ScrapeError::NotFound, not_found).await
                 }
-                m.embed(|e| {
-                    if !user.avatar.is_empty() {
-                        e.thumbnail(user.avatar);
-                    }
-                    e.author(|a| a
-                        .icon_url("https://cdn.discordapp.com/emojis/710193146843365457.webp?size=96&quality=lossless")
-                        .name(user.username)
-                    )
-                    .title(user.name)
-                    .description(description)
-                    .color(color)
-                    .url(user.url)
-                    .footer(|f| f.text(format!("{} follower{}, {}", user.followers, ["", "s"][(user.followers.replace(',', "").parse::<i32>().unwrap() > 1) as usize], user.films_count)))
-                })}).await?;
-    } else {
-        let error_message = ctx.say(format!(
-                "Couldn't find `{}` user.\nMake sure to provide your Letterboxd **username**, not the link.",
-                username
-            )).await?;
+                Picked::Cancelled => return Ok(()),
+            }
+        }
+    };
+    let review = match ctx.data().review(&key, &path).await {
+        Ok(review) => review,
+        Err(why) => {
+            let not_logged = format!("`{}` hasn't logged `{}`.", username, title);
+            return scrape_error(ctx, why, not_logged).await;
+        }
+    };
+    if review.text.is_empty() {
+        let error_message = ctx
+            .say(format!(
+                "`{}` logged {} without reviewing it.",
+                username, review.film
+            ))
+            .await?;
         sleep(5).await;
         error_message.delete(ctx).await?;
+        return Ok(());
     }
+    let color = ctx_util::color(ctx).await;
+    ctx.send(|m| m.embed(|e| render::review(e, &review, color)))
+        .await?;
     Ok(())
 }
 
-/// A roulette to get a random film off Letterboxd.
+/// Get a Letterboxd profile information.
 #[poise::command(slash_command)]
-pub async fn roulette(ctx: Context<'_>) -> Result<(), Error> {
+pub async fn profile(
+    ctx: Context<'_>,
+    #[description = "The profile username, defaults to your linked account."]
+    #[autocomplete = "autocomplete::username"]
+    username: Option<String>,
+    #[description = "A member whose linked account to look up."] user: Option<serenity::Member>,
+) -> Result<(), Error> {
     ctx.defer().await?;
-    let wait = ctx.say("Please wait...").await?;
-    let handle = Handle::current();
-    let film_info = spawn_blocking(move || {
-        lbxd_util::get_roulette().unwrap_or_else(|_| {
-            lbxd_util::get_roulette().unwrap_or_else(|_| lbxd_util::get_roulette().unwrap())
+    let Some(username) = resolve_username(ctx, username, user).await? else {
+        return Ok(());
+    };
+    let user = match ctx.data().profile(&username).await {
+        Ok(user) => user,
+        Err(why) => return scrape_error(ctx, why, user_not_found(&username)).await,
+    };
+    let color = ctx_util::color(ctx).await;
+    ctx.send(|m| {
+            if !user.websites.is_empty() {
+                m.components(|c| {
+                    c.create_action_row(|ar| {
+                        if user.websites.len() == 1 {
+                            if !user.websites[0].contains("twitter") {
+                                ar.create_button(|b| {
+                                b.style(serenity::ButtonStyle::Link).label("Website").url(&user.websites[0]).emoji(Unicode("🌐".to_string()))
+                            })
+                            } else {
+                                ar.create_button(|b| {
+                                b.style(serenity::ButtonStyle::Link).label("Twitter").url(&user.websites[0]).emoji(EmojiIdentifier { animated: false , id: EmojiId(1083962148633456670), name: "twt".to_string()})
+                            })
+                            }
+                        } else {
+                            ar.create_button(|b| {
+                                b.style(serenity::ButtonStyle::Link).label("Website").url(&user.websites[0]).emoji(Unicode("🌐".to_string()))
+                            });
+                            ar.create_button(|b| {
+                                b.style(serenity::ButtonStyle::Link).label("Twitter").url(&user.websites[1]).emoji(EmojiIdentifier { animated: false , id: EmojiId(1083962148633456670), name: "twt".to_string()})
+                            })
+                        }
+                    })
+                });
+            }
+            m.embed(|e| render::profile(e, &user, color))
+        }).await?;
+    Ok(())
+}
+
+/// How a roulette spin ended.
+enum Spin {
+    Found(Film),
+    Cancelled,
+    Failed(ScrapeError),
+}
+
+fn roulette_button<'a>(
+    c: &'a mut serenity::CreateComponents,
+    custom_id: &str,
+    label: &str,
+    emoji: &str,
+) -> &'a mut serenity::CreateComponents {
+    c.create_action_row(|ar| {
+        ar.create_button(|b| {
+            b.custom_id(custom_id)
+                .style(serenity::ButtonStyle::Secondary)
+                .label(label)
+                .emoji(Unicode(emoji.to_string()))
         })
     })
-    .await
-    .unwrap();
-    drop(handle);
-    wait.edit(ctx, |m| m.content("Fetching information..."))
-        .await?;
-    let color = ctx
-        .author_member()
-        .await
-        .unwrap()
-        .colour(&ctx.serenity_context().cache)
-        .unwrap();
-    let tagline = if !film_info.tagline.is_empty() {
-        format!("**{}**\n", film_info.tagline)
-    } else {
-        String::new()
+}
+
+/// Spins the roulette until a film comes up, it takes longer than
+/// [`config::ROULETTE_TIMEOUT`] or the author presses the `cancel_id` button.
+async fn spin(
+    ctx: Context<'_>,
+    filter: &lbxd_util::RouletteFilter,
+    cancel_id: &str,
+) -> Result<Spin, Error> {
+    let lbxd = &ctx.data().lbxd;
+    let roulette = lbxd.get_roulette(filter);
+    let cancel_id = cancel_id.to_string();
+    let cancel = serenity::CollectComponentInteraction::new(ctx)
+        .filter(move |press| press.data.custom_id == cancel_id)
+        .author_id(ctx.author().id)
+        .timeout(config::ROULETTE_TIMEOUT);
+    tokio::select! {
+        spun = tokio::time::timeout(config::ROULETTE_TIMEOUT, roulette) => Ok(match spun {
+            Ok(Ok(film_info)) => Spin::Found(film_info),
+            Ok(Err(why)) => Spin::Failed(why),
+            Err(_) => Spin::Failed(ScrapeError::NotFound),
+        }),
+        Some(press) = cancel => {
+            press
+                .create_interaction_response(ctx, |r| {
+                    r.kind(serenity::InteractionResponseType::DeferredUpdateMessage)
+                })
+                .await?;
+            Ok(Spin::Cancelled)
+        }
+    }
+}
+
+/// A roulette to get a random film off Letterboxd.
+#[poise::command(slash_command)]
+pub async fn roulette(
+    ctx: Context<'_>,
+    #[description = "Only films of this genre, e.g. Horror."] genre: Option<String>,
+    #[description = "Only films of this decade, e.g. 1980."]
+    #[min = 1870]
+    decade: Option<i32>,
+    #[description = "The earliest release year, alone it picks that exact year."]
+    #[min = 1870]
+    year_from: Option<i32>,
+    #[description = "The latest release year, alone it picks that exact year."]
+    #[min = 1870]
+    year_to: Option<i32>,
+    #[description = "Only films at most this many minutes long."]
+    #[min = 1]
+    max_runtime: Option<u32>,
+    #[description = "Only films rated at least this many stars on average."]
+    #[min = 0.5]
+    #[max = 5]
+    min_rating: Option<f32>,
+    #[description = "Only films from this country, e.g. USA."] country: Option<String>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    if decade.is_some() && (year_from.is_some() || year_to.is_some()) {
+        let error_message = ctx.say("Pick either a decade or years, not both.").await?;
+        sleep(5).await;
+        error_message.delete(ctx).await?;
+        return Ok(());
+    }
+    let years = match (year_from, year_to) {
+        (Some(from), Some(to)) => Some((from.min(to), from.max(to))),
+        (Some(year), None) | (None, Some(year)) => Some((year, year)),
+        (None, None) => None,
     };
-    let rating = if film_info.rating != *" 0.0" {
-        format!("{}\n", film_info.rating)
-    } else {
-        "".to_string()
+    if ctx_util::reject_future_year(ctx, decade.max(year_from).max(year_to)).await? {
+        return Ok(());
+    }
+    let filter = lbxd_util::RouletteFilter {
+        genre,
+        decade: decade.map(|decade| decade / 10 * 10),
+        years,
+        country,
+        max_runtime,
+        min_rating,
     };
-    let plural_check = vec!["", "s"][(film_info.directors.split(", ").count() > 1) as usize];
-    let country_check = if !film_info.countries.is_empty() {
-        "|"
+    let not_found = if filter.is_empty() {
+        "Couldn't find a film, try again."
     } else {
-        ""
+        "Couldn't find a film like that, check the genre and country or loosen the filters."
     };
-    let duration = if film_info.duration != *"0m" {
-        format!("{}\n", film_info.duration)
-    } else {
-        "".to_string()
-    };
-    wait.edit(ctx, |m| {
-        m.content("").embed(|e| {
-            e.title(film_info.title)
-                .description(format!(
-                    "{}{}\n\n{}Director{}: {}\n{} {} {}\n{}\u{1f440} {} | ❤️ {} | \u{1f4ac} {}",
-                    tagline,
-                    film_info.synopsis,
-                    rating,
-                    plural_check,
-                    film_info.directors,
-                    film_info.countries,
-                    country_check,
-                    film_info.genre,
-                    duration,
-                    film_info.info["people"],
-                    film_info.info["likes"],
-                    film_info.info.get("reviews").unwrap_or(&"0".to_string())
-                ))
-                .url(film_info.url)
-                .color(color)
-                .thumbnail(film_info.poster)
+    let color = ctx_util::color(ctx).await;
+    let cancel_id = format!("{}cancel", ctx.id());
+    let reroll_id = format!("{}reroll", ctx.id());
+    let reply = ctx
+        .send(|m| {
+            m.content("Please wait...")
+                .components(|c| roulette_button(c, &cancel_id, "Cancel", "✖️"))
         })
-    })
-    .await?;
-    Ok(())
+        .await?;
+    loop {
+        let film_info = match spin(ctx, &filter, &cancel_id).await? {
+            Spin::Found(film_info) => film_info,
+            Spin::Cancelled => {
+                reply
+                    .edit(ctx, |m| m.content("Cancelled.").components(|c| c))
+                    .await?;
+                return Ok(());
+            }
+            Spin::Failed(why) => {
+                reply.delete(ctx).await?;
+                return scrape_error(ctx, why, not_found.to_string()).await;
+            }
+        };
+        ctx.data()
+            .film_cache
+            .insert(keys::film_slug(&film_info.slug), film_info.clone());
+        reply
+            .edit(ctx, |m| {
+                m.content("")
+                    .embed(|e| render::film(e, &film_info, color))
+                    .components(|c| roulette_button(c, &reroll_id, "Reroll", "🎲"))
+            })
+            .await?;
+        let reroll_filter = reroll_id.clone();
+        let Some(press) = serenity::CollectComponentInteraction::new(ctx)
+            .filter(move |press| press.data.custom_id == reroll_filter)
+            .author_id(ctx.author().id)
+            .timeout(config::ROULETTE_REROLL_TIMEOUT)
+            .await
+        else {
+            reply
+                .edit(ctx, |m| {
+                    m.embed(|e| render::film(e, &film_info, color))
+                        .components(|c| c)
+                })
+                .await?;
+            return Ok(());
+        };
+        press
+            .create_interaction_response(ctx, |r| {
+                r.kind(serenity::InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|d| {
+                        d.content("Please wait...")
+                            .set_embeds(Vec::new())
+                            .components(|c| roulette_button(c, &cancel_id, "Cancel", "✖️"))
+                    })
+            })
+            .await?;
+    }
 }
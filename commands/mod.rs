@@ -1,4 +1,5 @@
 pub mod cmds;
 pub mod film;
 pub mod letterboxd;
+pub mod person;
 pub mod utils;
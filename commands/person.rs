@@ -0,0 +1,219 @@
+use crate::commands::utils::{
+    ctx_util,
+    dates::Date,
+    keys,
+    paginator::{self, Action, Page},
+    picker::{self, Choice, Picked},
+    render, structs,
+    tmdb_util::{Credit, TmdbPerson},
+};
+use crate::config;
+
+use poise::serenity_prelude as serenity;
+
+type Context<'a> = poise::Context<'a, structs::Data, Error>;
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+async fn sleep(secs: u64) {
+    tokio::time::sleep(tokio::time::Duration::from_secs(secs)).await;
+}
+
+/// Gets a TMDB person by name, asking the author when several people share it.
+async fn get_person(ctx: Context<'_>, name: &str) -> Result<Picked<TmdbPerson>, Error> {
+    let data = ctx.data();
+    let key = keys::title(name, None);
+    if let Some(person) = data.person_cache.get(&key) {
+        return Ok(Picked::One(person));
+    }
+    let candidates = data.tmdb.search_people(name).await?;
+    let (candidate, remember) =
+        if !picker::is_ambiguous(name, candidates.iter().map(|c| c.name.as_str())) {
+            match candidates.into_iter().next() {
+                Some(candidate) => (candidate, true),
+                None => return Ok(Picked::NotFound),
+            }
+        } else {
+            let choices = candidates
+                .iter()
+                .take(picker::MAX_CHOICES)
+                .map(|candidate| {
+                    let known_for = candidate.known_for.join(", ");
+                    let description = [candidate.department.clone(), Some(known_for)];
+                    Choice {
+                        label: candidate.name.clone(),
+                        description: description
+                            .into_iter()
+                            .flatten()
+                            .filter(|part| !part.is_empty())
+                            .collect::<Vec<_>>()
+                            .join(" • "),
+                    }
+                })
+                .collect::<Vec<_>>();
+            let prompt = format!("There are a few people called `{}`, which one?", name);
+            match picker::pick(ctx, &prompt, &choices).await? {
+                Some(i) if i < candidates.len() => (candidates[i].clone(), false),
+                _ => return Ok(Picked::Cancelled),
+            }
+        };
+    let person = data.person(candidate.id).await?;
+    Ok(if remember {
+        data.person_cache
+            .alias(key, keys::tmdb_person(candidate.id));
+        Picked::One(person)
+    } else {
+        Picked::Chosen(person)
+    })
+}
+
+/// The first page of a person, with their biography and what they're known for.
+fn profile_page(person: &TmdbPerson) -> Page {
+    let mut description = String::new();
+    if !person.biography.is_empty() {
+        description.push_str(&format!("{}\n\n", render::truncate(&person.biography, 600)));
+    }
+    let known_for = person.known_for(5);
+    if !known_for.is_empty() {
+        description.push_str(&format!("**Known for:** {}\n", known_for.join(", ")));
+    }
+    if let Some(born) = person.birthday.as_deref().and_then(Date::parse_iso) {
+        description.push_str(&format!("**Born:** <t:{}:D>", born.timestamp()));
+        if let Some(place) = person.place_of_birth.as_ref().filter(|p| !p.is_empty()) {
+            description.push_str(&format!(" in {}", place));
+        }
+        description.push('\n');
+    }
+    if let Some(died) = person.deathday.as_deref().and_then(Date::parse_iso) {
+        description.push_str(&format!("**Died:** <t:{}:D>\n", died.timestamp()));
+    }
+    Page {
+        title: person.name.clone(),
+        url: Some(person.url()),
+        description: Some(description),
+        thumbnail: person.profile(),
+        footer: person.known_for_department.clone(),
+        ..Default::default()
+    }
+}
+
+/// Pages through `credits`, each film getting a button that opens it like `/film info`.
+fn filmography_pages(
+    ctx_id: u64,
+    person: &TmdbPerson,
+    heading: &str,
+    credits: &[Credit],
+) -> Vec<Page> {
+    credits
+        .chunks(config::FILMOGRAPHY_PER_PAGE)
+        .enumerate()
+        .map(|(page, chunk)| {
+            let first = page * config::FILMOGRAPHY_PER_PAGE + 1;
+            let lines = chunk
+                .iter()
+                .enumerate()
+                .map(|(i, credit)| match &credit.role {
+                    Some(role) => {
+                        format!("`{}.` {} as {}", first + i, credit.display_title(), role)
+                    }
+                    None => format!("`{}.` {}", first + i, credit.display_title()),
+                })
+                .collect::<Vec<_>>();
+            let actions = chunk
+                .iter()
+                .enumerate()
+                .map(|(i, credit)| Action {
+                    custom_id: format!("{}open{}", ctx_id, credit.id),
+                    label: (first + i).to_string(),
+                })
+                .collect();
+            Page {
+                title: format!("{} • {}", person.name, heading),
+                url: Some(person.url()),
+                description: Some(lines.join("\n")),
+                thumbnail: person.profile(),
+                actions,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Answers the film buttons of a filmography until they time out.
+///
+/// Anyone can press them, the film is only shown to whoever did.
+async fn open_films(ctx: Context<'_>, color: serenity::Colour) -> Result<(), Error> {
+    let prefix = format!("{}open", ctx.id());
+    let filter_prefix = prefix.clone();
+    let region = ctx.data().region(ctx.guild_id().map(|id| id.0));
+    while let Some(press) = serenity::CollectComponentInteraction::new(ctx)
+        .filter(move |press| press.data.custom_id.starts_with(&filter_prefix))
+        .timeout(paginator::TIMEOUT)
+        .await
+    {
+        let Ok(id) = press.data.custom_id[prefix.len()..].parse::<u64>() else {
+            continue;
+        };
+        // TMDB might take longer than Discord waits for an answer.
+        press
+            .create_interaction_response(ctx, |r| {
+                r.kind(serenity::InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|d| d.ephemeral(true))
+            })
+            .await?;
+        let film = ctx.data().details(id).await;
+        press
+            .create_followup_message(ctx, |f| {
+                f.ephemeral(true);
+                match &film {
+                    Ok(film) => f.embed(|e| render::tmdb_film(e, film, &region, color)),
+                    Err(why) => f.content(format!("Couldn't get that film: {}.", why)),
+                }
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+/// Get an actor's or filmmaker's biography and filmography from TMDB.
+#[poise::command(slash_command)]
+pub async fn person(
+    ctx: Context<'_>,
+    #[description = "The person's name."] name: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+    let color = ctx_util::color(ctx).await;
+    let person = match get_person(ctx, &name).await? {
+        Picked::One(person) | Picked::Chosen(person) => person,
+        Picked::NotFound => {
+            let error_message = ctx.say(format!("Couldn't find `{}`.", name)).await?;
+            sleep(5).await;
+            error_message.delete(ctx).await?;
+            return Ok(());
+        }
+        Picked::Cancelled => return Ok(()),
+    };
+    let mut pages = vec![profile_page(&person)];
+    pages.extend(filmography_pages(
+        ctx.id(),
+        &person,
+        "Acting",
+        &person.acting(),
+    ));
+    pages.extend(filmography_pages(
+        ctx.id(),
+        &person,
+        "Directing",
+        &person.directing(),
+    ));
+    let author = *ctx.author().id.as_u64();
+    if pages.iter().all(|page| page.actions.is_empty()) {
+        paginator::start(ctx, color, author, &pages).await?;
+        return Ok(());
+    }
+    let (paged, opened) = tokio::join!(
+        paginator::start(ctx, color, author, &pages),
+        open_films(ctx, color),
+    );
+    paged?;
+    opened
+}
@@ -0,0 +1,143 @@
+//! Slash command autocomplete for film titles and Letterboxd usernames.
+
+use crate::commands::utils::{keys, structs::Data};
+use crate::config;
+
+use std::{collections::HashMap, sync::Mutex};
+
+type Context<'a> = poise::Context<'a, Data, Error>;
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Discord shows at most 25 suggestions.
+const MAX_SUGGESTIONS: usize = 25;
+
+/// Shorter queries match too much to be worth a TMDB search.
+const MIN_QUERY_CHARS: usize = 2;
+
+/// Discord rejects the whole response if a single suggestion is longer than this.
+const MAX_SUGGESTION_CHARS: usize = 100;
+
+/// Drops autocomplete requests superseded by a newer keystroke from the same user.
+#[derive(Debug, Default)]
+pub struct Debouncer {
+    latest: Mutex<HashMap<u64, u64>>,
+}
+
+impl Debouncer {
+    /// Waits a bit and tells whether request `id` is still the latest one from `user`.
+    pub async fn settle(&self, user: u64, id: u64) -> bool {
+        self.latest.lock().unwrap().insert(user, id);
+        tokio::time::sleep(config::AUTOCOMPLETE_DEBOUNCE).await;
+        let mut latest = self.latest.lock().unwrap();
+        if latest.get(&user) != Some(&id) {
+            return false;
+        }
+        latest.remove(&user);
+        true
+    }
+}
+
+/// Splits a picked suggestion like `Alien (1979)` back into its title and year.
+///
+/// An explicit `year` wins over the one in the title.
+pub fn split_year(title: &str, year: Option<i32>) -> (String, Option<i32>) {
+    let title = title.trim();
+    let parsed = title
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" ("))
+        .filter(|(name, suffix)| !name.is_empty() && suffix.len() == 4)
+        .and_then(|(name, suffix)| Some((name, suffix.parse::<i32>().ok()?)));
+    match parsed {
+        Some((name, parsed_year)) => (name.to_string(), year.or(Some(parsed_year))),
+        None => (title.to_string(), year),
+    }
+}
+
+/// Suggests films from TMDB as `Title (year)`, followed by the original title when it's
+/// different.
+pub async fn film_title(
+    ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = poise::AutocompleteChoice<String>> {
+    let query = partial.trim();
+    if query.chars().count() < MIN_QUERY_CHARS {
+        return vec![].into_iter();
+    }
+    let data = ctx.data();
+    let key = keys::title(query, None);
+    let candidates = match data.suggestion_cache.get(&key) {
+        Some(candidates) => candidates,
+        None => {
+            if !data
+                .debouncer
+                .settle(*ctx.author().id.as_u64(), ctx.id())
+                .await
+            {
+                return vec![].into_iter();
+            }
+            match data.tmdb.search(query, None).await {
+                Ok(candidates) => {
+                    data.suggestion_cache.insert(&key, candidates.clone());
+                    candidates
+                }
+                Err(why) => {
+                    eprintln!("Couldn't autocomplete `{}`: {}", query, why);
+                    vec![]
+                }
+            }
+        }
+    };
+    candidates
+        .into_iter()
+        .filter_map(|c| {
+            let title = c.display_title();
+            if title.chars().count() > MAX_SUGGESTION_CHARS {
+                return None;
+            }
+            // Only the name is shown, the value is what `split_year` gets back.
+            let name = c
+                .original()
+                .map(|original| format!("{} • {}", title, original))
+                .filter(|name| name.chars().count() <= MAX_SUGGESTION_CHARS)
+                .unwrap_or_else(|| title.clone());
+            Some(poise::AutocompleteChoice { name, value: title })
+        })
+        .take(MAX_SUGGESTIONS)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// Suggests usernames that were looked up or linked before.
+pub async fn username(ctx: Context<'_>, partial: &str) -> impl Iterator<Item = String> {
+    let partial = keys::username(partial);
+    let data = ctx.data();
+    let mut usernames = data.usernames.values();
+    // Linked accounts are kept for good, unlike looked up usernames.
+    for (_, link) in data.links.entries() {
+        if !usernames.contains(&link.username) {
+            usernames.push(link.username);
+        }
+    }
+    // Prefix matches first, then anything containing what was typed.
+    usernames.retain(|username| username.contains(&partial));
+    usernames.sort_by_key(|username| !username.starts_with(&partial));
+    usernames.truncate(MAX_SUGGESTIONS);
+    usernames.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_years() {
+        assert_eq!(split_year("Alien (1979)", None), ("Alien".into(), Some(1979)));
+        assert_eq!(split_year("Alien (1979)", Some(1986)), ("Alien".into(), Some(1986)));
+        assert_eq!(split_year(" Alien ", None), ("Alien".into(), None));
+        assert_eq!(
+            split_year("Airplane II (The Sequel)", None),
+            ("Airplane II (The Sequel)".into(), None)
+        );
+        assert_eq!(split_year("(1979)", None), ("(1979)".into(), None));
+    }
+}
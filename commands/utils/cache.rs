@@ -0,0 +1,304 @@
+use crate::commands::utils::store;
+use crate::config;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+static FLUSHING: AtomicBool = AtomicBool::new(false);
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry<V> {
+    value: V,
+    expires_at: u64,
+    #[serde(skip)]
+    last_used: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Inner<V> {
+    entries: HashMap<String, Entry<V>>,
+    /// Alternative keys pointing at a canonical key in `entries`.
+    #[serde(default)]
+    aliases: HashMap<String, String>,
+    #[serde(skip)]
+    clock: u64,
+}
+
+impl<V> Inner<V> {
+    fn resolve<'a>(&'a self, key: &'a str) -> &'a str {
+        self.aliases.get(key).map(|k| k.as_str()).unwrap_or(key)
+    }
+
+    fn prune(&mut self) {
+        let now = now();
+        self.entries.retain(|_, entry| entry.expires_at > now);
+        let entries = &self.entries;
+        self.aliases.retain(|_, key| entries.contains_key(key));
+    }
+}
+
+/// The part of a cache its flusher shares.
+#[derive(Debug)]
+struct State<V> {
+    name: &'static str,
+    path: Option<PathBuf>,
+    /// Whether `inner` changed since it was last written to `path`.
+    dirty: AtomicBool,
+    inner: Mutex<Inner<V>>,
+}
+
+/// Something holding changes that still have to be written to disk.
+pub trait Flush: Send + Sync {
+    fn flush(&self);
+}
+
+impl<V: Serialize + Send> Flush for State<V> {
+    fn flush(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        // Serialize under the lock, but write after releasing it.
+        let json = serde_json::to_string(&*self.inner.lock().unwrap());
+        let saved = match json {
+            Ok(json) => store::write_atomic(path, &json),
+            Err(why) => Err(why.to_string()),
+        };
+        if let Err(why) = saved {
+            eprintln!("Couldn't save the {} cache: {}", self.name, why);
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Writes the changes of `caches` to disk every [`config::CACHE_FLUSH_INTERVAL`], off the
+/// async runtime. Starts once, however many times the bot reconnects.
+pub fn spawn_flusher(caches: Vec<Arc<dyn Flush>>) {
+    if FLUSHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config::CACHE_FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let caches = caches.clone();
+            let flushed = tokio::task::spawn_blocking(move || {
+                for cache in caches {
+                    cache.flush();
+                }
+            });
+            if let Err(why) = flushed.await {
+                eprintln!("Couldn't flush the caches: {}", why);
+            }
+        }
+    });
+}
+
+/// A TTL cache capped at `capacity` entries, evicting the least recently used one when full.
+///
+/// When given a directory through [`Cache::persist_to`] the entries are mirrored to
+/// `<dir>/<name>.json`, so warm data survives restarts. Changes are only written by
+/// [`spawn_flusher`], so lookups never wait on the disk.
+#[derive(Debug)]
+pub struct Cache<V> {
+    ttl: Duration,
+    capacity: usize,
+    state: Arc<State<V>>,
+}
+
+impl<V: Clone + Serialize + DeserializeOwned> Cache<V> {
+    pub fn new(name: &'static str, ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            state: Arc::new(State {
+                name,
+                path: None,
+                dirty: AtomicBool::new(false),
+                inner: Mutex::new(Inner {
+                    entries: HashMap::new(),
+                    aliases: HashMap::new(),
+                    clock: 0,
+                }),
+            }),
+        }
+    }
+
+    /// Loads whatever was saved in `dir` and keeps saving there from now on.
+    pub fn persist_to(mut self, dir: Option<&str>) -> Self {
+        let Some(dir) = dir else {
+            return self;
+        };
+        // Nothing else holds the state before it's persisted.
+        let state = Arc::get_mut(&mut self.state).unwrap();
+        let path = PathBuf::from(dir).join(format!("{}.json", state.name));
+        if let Ok(saved) = fs::read_to_string(&path) {
+            match serde_json::from_str::<Inner<V>>(&saved) {
+                Ok(mut inner) => {
+                    inner.prune();
+                    *state.inner.get_mut().unwrap() = inner;
+                }
+                Err(why) => eprintln!("Discarding the {} cache: {}", state.name, why),
+            }
+        }
+        state.path = Some(path);
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<V> {
+        let mut inner = self.state.inner.lock().unwrap();
+        inner.clock += 1;
+        let clock = inner.clock;
+        let key = inner.resolve(key).to_string();
+        let expired = match inner.entries.get_mut(&key) {
+            Some(entry) if entry.expires_at > now() => {
+                entry.last_used = clock;
+                return Some(entry.value.clone());
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            inner.prune();
+        }
+        None
+    }
+
+    pub fn insert(&self, key: impl Into<String>, value: V) {
+        let mut inner = self.state.inner.lock().unwrap();
+        inner.clock += 1;
+        inner.prune();
+        let entry = Entry {
+            value,
+            expires_at: now() + self.ttl.as_secs(),
+            last_used: inner.clock,
+        };
+        inner.entries.insert(key.into(), entry);
+        while inner.entries.len() > self.capacity {
+            let oldest = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => inner.entries.remove(&key),
+                None => break,
+            };
+        }
+        inner.prune();
+        self.touch();
+    }
+
+    /// Makes `alias` resolve to the entry stored under `key`.
+    pub fn alias(&self, alias: impl Into<String>, key: impl Into<String>) {
+        let (alias, key) = (alias.into(), key.into());
+        if alias == key {
+            return;
+        }
+        let mut inner = self.state.inner.lock().unwrap();
+        inner.aliases.insert(alias, key);
+        self.touch();
+    }
+
+    /// Every live value, most recently used first.
+    pub fn values(&self) -> Vec<V> {
+        let inner = self.state.inner.lock().unwrap();
+        let now = now();
+        let mut entries = inner
+            .entries
+            .values()
+            .filter(|entry| entry.expires_at > now)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.last_used));
+        entries.into_iter().map(|entry| entry.value.clone()).collect()
+    }
+
+    /// Marks the cache as changed since it was last written.
+    fn touch(&self) {
+        self.state.dirty.store(true, Ordering::SeqCst);
+    }
+}
+
+impl<V: Serialize + Send + 'static> Cache<V> {
+    /// What writes this cache's changes to disk, for [`spawn_flusher`].
+    pub fn flusher(&self) -> Arc<dyn Flush> {
+        self.state.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = Cache::new("test", Duration::from_secs(60), 2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        assert_eq!(cache.get("a"), Some(1));
+        cache.insert("c", 3);
+        assert_eq!(cache.get("a"), Some(1));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(3));
+    }
+
+    #[test]
+    fn resolves_aliases() {
+        let cache = Cache::new("test", Duration::from_secs(60), 2);
+        cache.insert("slug:alien", 1);
+        cache.alias("alien", "slug:alien");
+        assert_eq!(cache.get("alien"), Some(1));
+        assert_eq!(cache.get("other"), None);
+    }
+
+    #[test]
+    fn lists_recently_used_first() {
+        let cache = Cache::new("test", Duration::from_secs(60), 3);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.get("a");
+        assert_eq!(cache.values(), vec![1, 2]);
+    }
+
+    #[test]
+    fn survives_reloading() {
+        let dir = std::env::temp_dir().join(format!("filmbro-cache-{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let cache = Cache::new("test", Duration::from_secs(60), 2).persist_to(Some(dir));
+        cache.insert("slug:alien", 1);
+        cache.alias("alien", "slug:alien");
+        let path = PathBuf::from(dir).join("test.json");
+        assert!(!path.exists());
+        cache.flusher().flush();
+
+        let reloaded = Cache::<i32>::new("test", Duration::from_secs(60), 2).persist_to(Some(dir));
+        assert_eq!(reloaded.get("alien"), Some(1));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn expires_entries() {
+        let cache = Cache::new("test", Duration::from_secs(0), 2);
+        cache.insert("a", 1);
+        assert_eq!(cache.get("a"), None);
+    }
+}
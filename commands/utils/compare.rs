@@ -0,0 +1,164 @@
+//! Taste compatibility between two members, from the films they both rated.
+
+use crate::commands::utils::structs::RatedFilm;
+
+use std::collections::HashMap;
+
+/// How many disagreements and shared favorites are kept.
+const MAX_HIGHLIGHTS: usize = 5;
+
+/// A film both members rated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedRating {
+    pub title: String,
+    pub path: String,
+    pub first: f32,
+    pub second: f32,
+}
+
+impl SharedRating {
+    fn difference(&self) -> f32 {
+        (self.first - self.second).abs()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Comparison {
+    /// How many films both members rated.
+    pub overlap: usize,
+    /// Pearson correlation of the ratings, `None` with too few films or when either member
+    /// rated them all the same.
+    pub correlation: Option<f64>,
+    /// The mean absolute difference between the ratings, in stars.
+    pub mean_difference: Option<f64>,
+    /// The films rated furthest apart, biggest difference first.
+    pub disagreements: Vec<SharedRating>,
+    /// The films both rated five stars.
+    pub favorites: Vec<SharedRating>,
+}
+
+fn correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+    Some(covariance / (variance_x * variance_y).sqrt())
+}
+
+/// Compares the ratings of two members.
+pub fn compare(first: &[RatedFilm], second: &[RatedFilm]) -> Comparison {
+    let second = second
+        .iter()
+        .map(|film| (film.path.as_str(), film.stars))
+        .collect::<HashMap<_, _>>();
+    let shared = first
+        .iter()
+        .filter_map(|film| {
+            Some(SharedRating {
+                title: film.title.clone(),
+                path: film.path.clone(),
+                first: film.stars,
+                second: *second.get(film.path.as_str())?,
+            })
+        })
+        .collect::<Vec<_>>();
+    if shared.is_empty() {
+        return Comparison::default();
+    }
+    let pairs = shared
+        .iter()
+        .map(|s| (s.first as f64, s.second as f64))
+        .collect::<Vec<_>>();
+    let mean_difference =
+        shared.iter().map(|s| s.difference() as f64).sum::<f64>() / shared.len() as f64;
+    let mut disagreements = shared
+        .iter()
+        .filter(|s| s.difference() > 0.0)
+        .cloned()
+        .collect::<Vec<_>>();
+    // Stable, so ties keep the first member's order.
+    disagreements.sort_by(|a, b| b.difference().total_cmp(&a.difference()));
+    disagreements.truncate(MAX_HIGHLIGHTS);
+    let favorites = shared
+        .iter()
+        .filter(|s| s.first == 5.0 && s.second == 5.0)
+        .take(MAX_HIGHLIGHTS)
+        .cloned()
+        .collect();
+    Comparison {
+        overlap: shared.len(),
+        correlation: correlation(&pairs),
+        mean_difference: Some(mean_difference),
+        disagreements,
+        favorites,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rated(path: &str, stars: f32) -> RatedFilm {
+        RatedFilm {
+            title: path.to_string(),
+            path: path.to_string(),
+            stars,
+        }
+    }
+
+    #[test]
+    fn compares_shared_ratings() {
+        let first = vec![
+            rated("alien", 5.0),
+            rated("heat", 4.0),
+            rated("jaws", 1.0),
+            rated("cats", 3.0),
+        ];
+        let second = vec![
+            rated("jaws", 5.0),
+            rated("alien", 5.0),
+            rated("heat", 3.5),
+            rated("tron", 2.0),
+        ];
+        let comparison = compare(&first, &second);
+        assert_eq!(comparison.overlap, 3);
+        assert_eq!(comparison.mean_difference, Some(1.5));
+        assert_eq!(
+            comparison
+                .disagreements
+                .iter()
+                .map(|s| s.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["jaws", "heat"]
+        );
+        assert_eq!(comparison.favorites.len(), 1);
+        assert_eq!(comparison.favorites[0].path, "alien");
+        assert!(comparison.correlation.unwrap() < 0.0);
+    }
+
+    #[test]
+    fn correlations() {
+        assert_eq!(correlation(&[(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)]), Some(1.0));
+        assert_eq!(correlation(&[(1.0, 3.0), (2.0, 3.0)]), None);
+        assert_eq!(correlation(&[(1.0, 1.0)]), None);
+    }
+
+    #[test]
+    fn nothing_in_common() {
+        let comparison = compare(&[rated("alien", 5.0)], &[rated("heat", 5.0)]);
+        assert_eq!(comparison.overlap, 0);
+        assert!(comparison.correlation.is_none());
+        assert!(comparison.mean_difference.is_none());
+    }
+}
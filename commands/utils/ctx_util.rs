@@ -0,0 +1,30 @@
+//! Replies and lookups around the command context shared by several command groups.
+
+use crate::commands::utils::{dates, render};
+
+use poise::serenity_prelude as serenity;
+
+/// Tells the author no film comes out in `year` if it's past [`dates::latest_release_year`],
+/// returning whether it was.
+pub async fn reject_future_year<U, E>(
+    ctx: poise::Context<'_, U, E>,
+    year: Option<i32>,
+) -> Result<bool, serenity::Error> {
+    let Some(year) = year.filter(|year| *year > dates::latest_release_year()) else {
+        return Ok(false);
+    };
+    let error_message = ctx.say(format!("No film comes out in {}.", year)).await?;
+    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+    error_message.delete(ctx).await?;
+    Ok(true)
+}
+
+/// The author's role colour, [`render::LETTERBOXD_GREEN`] in DMs or without a coloured role.
+pub async fn color<U, E>(ctx: poise::Context<'_, U, E>) -> serenity::Colour {
+    let Some(member) = ctx.author_member().await else {
+        return render::LETTERBOXD_GREEN;
+    };
+    member
+        .colour(&ctx.serenity_context().cache)
+        .unwrap_or(render::LETTERBOXD_GREEN)
+}
@@ -0,0 +1,191 @@
+//! Calendar dates of diary entries, and the release years films can have.
+
+use crate::config;
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// How many days ago a date still gets described in words rather than shown as a timestamp.
+const RELATIVE_DAYS: i64 = 6;
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A day of the Gregorian calendar, with no time zone attached.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// `None` unless the day exists.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let exists = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+        exists.then_some(Self { year, month, day })
+    }
+
+    /// Parses a `2023-03-14` date, like the diary feed's.
+    pub fn parse_iso(date: &str) -> Option<Self> {
+        match date.split('-').collect::<Vec<_>>()[..] {
+            [year, month, day] => {
+                Self::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses a `14 Mar 2023` date, like the diary page's.
+    pub fn parse_diary(date: &str) -> Option<Self> {
+        match date.split(' ').collect::<Vec<_>>()[..] {
+            [day, month, year] => {
+                let month = MONTHS.iter().position(|m| *m == month)? as u32 + 1;
+                Self::new(year.parse().ok()?, month, day.parse().ok()?)
+            }
+            _ => None,
+        }
+    }
+
+    /// The current day in UTC.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        Self::from_days(seconds.div_euclid(SECONDS_PER_DAY))
+    }
+
+    /// The day `days` days after 1970-01-01.
+    fn from_days(days: i64) -> Self {
+        // Counts from 0000-03-01 so leap days end the 400-year eras.
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        Self {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    /// How many days after 1970-01-01 this is.
+    fn days(&self) -> i64 {
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let shifted_month = (self.month as i64 + 9) % 12;
+        let day_of_year = (153 * shifted_month + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// Noon UTC of this day as a Unix timestamp, which falls on the same day in nearly every
+    /// time zone Discord could render it in.
+    pub fn timestamp(&self) -> i64 {
+        self.days() * SECONDS_PER_DAY + SECONDS_PER_DAY / 2
+    }
+
+    /// How long before `today` this was in words, `None` past a few days or in the future.
+    pub fn days_ago(&self, today: Date) -> Option<String> {
+        match today.days() - self.days() {
+            0 => Some("Today".to_string()),
+            1 => Some("Yesterday".to_string()),
+            days @ 2..=RELATIVE_DAYS => Some(format!("{} days ago", days)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The latest year a film can be released in, counting the ones already announced.
+pub fn latest_release_year() -> i32 {
+    Date::today().year + config::UPCOMING_RELEASE_YEARS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(Date::parse_iso("2023-03-14"), Some(date(2023, 3, 14)));
+        assert_eq!(Date::parse_diary("02 Jan 2022"), Some(date(2022, 1, 2)));
+        assert_eq!(Date::parse_iso("2023-02-29"), None);
+        assert_eq!(Date::parse_iso("2024-02-29"), Some(date(2024, 2, 29)));
+        assert_eq!(Date::parse_diary("14 March 2023"), None);
+        assert_eq!(Date::parse_iso("yesterday"), None);
+    }
+
+    #[test]
+    fn counts_days() {
+        assert_eq!(Date::from_days(0), date(1970, 1, 1));
+        assert_eq!(date(2000, 3, 1).days(), 11_017);
+        assert_eq!(date(2023, 3, 14).timestamp(), 1_678_795_200);
+        for days in [-800_000, -1, 0, 59, 10_957, 19_430, 60_000] {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+    }
+
+    #[test]
+    fn days_ago() {
+        let today = date(2024, 3, 1);
+        assert_eq!(today.days_ago(today).as_deref(), Some("Today"));
+        assert_eq!(
+            date(2024, 2, 29).days_ago(today).as_deref(),
+            Some("Yesterday")
+        );
+        assert_eq!(
+            date(2024, 2, 24).days_ago(today).as_deref(),
+            Some("6 days ago")
+        );
+        assert_eq!(date(2024, 2, 23).days_ago(today), None);
+        assert_eq!(date(2024, 3, 2).days_ago(today), None);
+    }
+
+    #[test]
+    fn formats_iso_dates() {
+        assert_eq!(date(2022, 1, 2).to_string(), "2022-01-02");
+    }
+}
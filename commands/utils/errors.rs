@@ -0,0 +1,81 @@
+use std::fmt;
+
+/// Everything that can go wrong while scraping Letterboxd.
+#[derive(Debug)]
+pub enum ScrapeError {
+    /// The page (user, film, list...) doesn't exist.
+    NotFound,
+    /// The page exists but its content isn't public.
+    Private,
+    /// An element we rely on is gone, so Letterboxd probably changed their markup.
+    LayoutChanged { selector: String },
+    Http(reqwest::Error),
+    RateLimited,
+    Parse(String),
+}
+
+impl ScrapeError {
+    pub fn layout(selector: impl Into<String>) -> Self {
+        Self::LayoutChanged {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl fmt::Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "page not found"),
+            Self::Private => write!(f, "page is private"),
+            Self::LayoutChanged { selector } => {
+                write!(f, "couldn't find `{}` on the page", selector)
+            }
+            Self::Http(why) => write!(f, "HTTP error: {}", why),
+            Self::RateLimited => write!(f, "rate limited by Letterboxd"),
+            Self::Parse(why) => write!(f, "couldn't parse value: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for ScrapeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(why) => Some(why),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ScrapeError {
+    fn from(why: reqwest::Error) -> Self {
+        match why.status() {
+            Some(reqwest::StatusCode::NOT_FOUND) => Self::NotFound,
+            Some(reqwest::StatusCode::TOO_MANY_REQUESTS) => Self::RateLimited,
+            _ => Self::Http(why),
+        }
+    }
+}
+
+impl From<std::num::ParseIntError> for ScrapeError {
+    fn from(why: std::num::ParseIntError) -> Self {
+        Self::Parse(why.to_string())
+    }
+}
+
+impl From<std::num::ParseFloatError> for ScrapeError {
+    fn from(why: std::num::ParseFloatError) -> Self {
+        Self::Parse(why.to_string())
+    }
+}
+
+impl From<std::str::ParseBoolError> for ScrapeError {
+    fn from(why: std::str::ParseBoolError) -> Self {
+        Self::Parse(why.to_string())
+    }
+}
+
+impl From<roxmltree::Error> for ScrapeError {
+    fn from(why: roxmltree::Error) -> Self {
+        Self::Parse(why.to_string())
+    }
+}
@@ -0,0 +1,211 @@
+//! Polls followed diaries and posts their new entries into the guild's channel.
+
+use crate::commands::utils::{
+    errors::ScrapeError,
+    lbxd_util::LetterboxdClient,
+    render,
+    store::Store,
+    structs::{DiaryResult, GuildSettings},
+};
+use crate::config;
+
+use poise::serenity_prelude as serenity;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// How many feed keys are remembered per follow, a bit more than a diary page shows.
+const SEEN_LIMIT: usize = 50;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Starts the watcher in the background, once, however many times the bot reconnects.
+pub fn spawn(
+    http: Arc<serenity::Http>,
+    lbxd: LetterboxdClient,
+    guilds: Arc<Store<GuildSettings>>,
+) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(watch(http, lbxd, guilds));
+}
+
+/// The entries of `entries` (newest first) not in `seen`, oldest first.
+fn fresh_entries<'a>(seen: &[String], entries: &'a [DiaryResult]) -> Vec<&'a DiaryResult> {
+    entries
+        .iter()
+        .rev()
+        .filter(|entry| !seen.contains(&entry.feed_key()))
+        .collect()
+}
+
+/// Remembers `keys` as posted, forgetting the oldest ones past [`SEEN_LIMIT`].
+pub fn mark_seen(seen: &mut Vec<String>, keys: impl IntoIterator<Item = String>) {
+    seen.extend(keys);
+    if seen.len() > SEEN_LIMIT {
+        seen.drain(..seen.len() - SEEN_LIMIT);
+    }
+}
+
+fn backoff(failures: u32) -> Duration {
+    (config::FEED_POLL_INTERVAL * 2u32.pow(failures.min(8))).min(config::FEED_MAX_BACKOFF)
+}
+
+async fn watch(
+    http: Arc<serenity::Http>,
+    lbxd: LetterboxdClient,
+    guilds: Arc<Store<GuildSettings>>,
+) {
+    // Usernames whose diary failed to load, with how many times in a row and when to retry.
+    let mut failing = HashMap::<String, (u32, Instant)>::new();
+    let mut rate_limits = 0;
+    loop {
+        let wait = match rate_limits {
+            0 => config::FEED_POLL_INTERVAL,
+            n => backoff(n),
+        };
+        tokio::time::sleep(wait).await;
+        match poll(&http, &lbxd, &guilds, &mut failing).await {
+            Ok(()) => rate_limits = 0,
+            Err(why) => {
+                rate_limits += 1;
+                eprintln!("Diary feed: {}, backing off for {:?}", why, backoff(rate_limits));
+            }
+        }
+    }
+}
+
+/// Checks every followed diary once, stopping early if Letterboxd rate limits us.
+async fn poll(
+    http: &serenity::Http,
+    lbxd: &LetterboxdClient,
+    guilds: &Store<GuildSettings>,
+    failing: &mut HashMap<String, (u32, Instant)>,
+) -> Result<(), ScrapeError> {
+    // Several guilds may follow the same member, their diary is only fetched once.
+    let usernames = guilds
+        .entries()
+        .into_iter()
+        .flat_map(|(_, settings)| settings.follows.into_iter().map(|f| f.username))
+        .collect::<BTreeSet<_>>();
+    for username in usernames {
+        if let Some((_, retry_at)) = failing.get(&username) {
+            if Instant::now() < *retry_at {
+                continue;
+            }
+        }
+        match lbxd.get_diary(&username).await {
+            Ok(diary) => {
+                failing.remove(&username);
+                announce(http, lbxd, guilds, &username, &diary).await;
+            }
+            Err(ScrapeError::RateLimited) => return Err(ScrapeError::RateLimited),
+            Err(why) => {
+                let (failures, retry_at) = failing
+                    .entry(username.clone())
+                    .or_insert((0, Instant::now()));
+                *failures += 1;
+                *retry_at = Instant::now() + backoff(*failures);
+                eprintln!("Diary feed: couldn't get `{}`'s diary: {}", username, why);
+            }
+        }
+        tokio::time::sleep(config::FEED_FETCH_DELAY).await;
+    }
+    Ok(())
+}
+
+/// Posts the entries of `username`'s diary each following guild hasn't seen yet.
+async fn announce(
+    http: &serenity::Http,
+    lbxd: &LetterboxdClient,
+    guilds: &Store<GuildSettings>,
+    username: &str,
+    (avatar, display_name, entries): &(Option<String>, String, Vec<DiaryResult>),
+) {
+    for (guild_id, settings) in guilds.entries() {
+        for follow in settings.follows.iter().filter(|f| f.username == username) {
+            let mut posted = vec![];
+            for entry in fresh_entries(&follow.seen, entries) {
+                let sent = serenity::ChannelId(follow.channel_id)
+                    .send_message(http, |m| {
+                        m.embed(|e| {
+                            e.author(|a| {
+                                if let Some(avatar) = avatar {
+                                    a.icon_url(avatar);
+                                }
+                                a.name(display_name)
+                                    .url(format!("{}/{}/", lbxd.base_url(), username))
+                            })
+                            .title(&entry.title)
+                            .url(&entry.url)
+                            .description(render::diary_line(entry))
+                            .color(render::LETTERBOXD_GREEN)
+                        })
+                    })
+                    .await;
+                // Most likely the channel is gone or we lost access, don't retry it forever.
+                if let Err(why) = sent {
+                    eprintln!(
+                        "Diary feed: couldn't post in channel {}: {}",
+                        follow.channel_id, why
+                    );
+                }
+                posted.push(entry.feed_key());
+            }
+            if posted.is_empty() {
+                continue;
+            }
+            // The settings may have changed while posting, so only touch this follow.
+            guilds.update(&guild_id, |settings| {
+                let follow = settings
+                    .follows
+                    .iter_mut()
+                    .find(|f| f.username == username && f.channel_id == follow.channel_id);
+                if let Some(follow) = follow {
+                    mark_seen(&mut follow.seen, posted);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::utils::dates::Date;
+
+    fn entry(title: &str) -> DiaryResult {
+        DiaryResult {
+            title: title.to_string(),
+            url: format!("https://letterboxd.com/film/{}/", title),
+            date: Date::new(2023, 3, 14).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn posts_unseen_entries_oldest_first() {
+        let entries = vec![entry("heat"), entry("alien"), entry("jaws")];
+        let seen = vec![entry("jaws").feed_key()];
+        let fresh = fresh_entries(&seen, &entries)
+            .into_iter()
+            .map(|e| e.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(fresh, vec!["alien", "heat"]);
+    }
+
+    #[test]
+    fn forgets_oldest_seen_entries() {
+        let mut seen = (0..SEEN_LIMIT).map(|i| i.to_string()).collect::<Vec<_>>();
+        mark_seen(&mut seen, vec!["new".to_string()]);
+        assert_eq!(seen.len(), SEEN_LIMIT);
+        assert_eq!(seen[0], "1");
+        assert_eq!(seen.last().unwrap(), "new");
+    }
+}
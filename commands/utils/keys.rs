@@ -0,0 +1,88 @@
+//! Cache key normalization, so `Alien`, ` alien ` and `ALIEN!` all hit the same entry.
+
+/// Case folds a title and strips punctuation and extra whitespace, keeping the year if any.
+pub fn title(title: &str, year: Option<i32>) -> String {
+    let folded = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    match year {
+        Some(year) => format!("{}|{}", folded, year),
+        None => folded,
+    }
+}
+
+/// Letterboxd usernames are case insensitive, and people often paste `@name` or a profile link.
+pub fn username(username: &str) -> String {
+    let username = username.trim().trim_end_matches('/');
+    let username = match username.split_once("letterboxd.com/") {
+        Some((_, path)) => path.split('/').next().unwrap_or_default(),
+        None => username.trim_start_matches('@'),
+    };
+    username.to_lowercase()
+}
+
+/// A page of a diary, `period` being the year or month it's limited to.
+pub fn diary_page(username: &str, period: Option<&str>, page: u32) -> String {
+    format!("{}|{}|{}", username, period.unwrap_or("all"), page)
+}
+
+/// A member's entry of a film, `film_path` being like `/film/alien/`.
+pub fn review(username: &str, film_path: &str) -> String {
+    format!("{}|{}", username, film_path.trim_matches('/'))
+}
+
+/// Turns a list name into its slug the way Letterboxd does, e.g. `Best of the 80s!` into
+/// `best-of-the-80s`. Slugs are left as they are.
+pub fn list_slug(name: &str) -> String {
+    title(name, None).replace(' ', "-")
+}
+
+/// A member's list, `slug` being like `best-of-the-80s`.
+pub fn list(username: &str, slug: &str) -> String {
+    format!("{}|{}", username, slug)
+}
+
+/// The canonical key for a film on Letterboxd.
+pub fn film_slug(slug: &str) -> String {
+    format!("slug:{}", slug)
+}
+
+/// The canonical key for a film on TMDB.
+pub fn tmdb_id(id: u64) -> String {
+    format!("tmdb:{}", id)
+}
+
+/// The canonical key for a person on TMDB, whose ids are apart from films'.
+pub fn tmdb_person(id: u64) -> String {
+    format!("tmdb-person:{}", id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles() {
+        assert_eq!(title("Alien", None), "alien");
+        assert_eq!(title("  ALIEN! ", None), "alien");
+        assert_eq!(title("Paris,  Texas", Some(1984)), "paris texas|1984");
+    }
+
+    #[test]
+    fn usernames() {
+        assert_eq!(username(" JaneDoe "), "janedoe");
+        assert_eq!(username("@janedoe"), "janedoe");
+        assert_eq!(username("https://letterboxd.com/JaneDoe/"), "janedoe");
+    }
+
+    #[test]
+    fn list_slugs() {
+        assert_eq!(list_slug("Best of the 80s!"), "best-of-the-80s");
+        assert_eq!(list_slug("best-of-the-80s"), "best-of-the-80s");
+    }
+}
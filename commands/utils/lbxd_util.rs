@@ -1,14 +1,13 @@
 #![allow(unused_variables, dead_code)]
 
-use crate::commands::utils::structs::*;
+use crate::commands::utils::{dates::Date, errors::ScrapeError, render::starrize, rss, structs::*};
+use crate::config;
 
 use html_escape::decode_html_entities as decode_html;
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
 use regex::Regex;
-use reqwest::{blocking::get as reqget, header::HeaderValue};
-use scraper::{Html, Selector};
-
-use std::collections::HashMap;
+use reqwest::header::HeaderValue;
+use scraper::{ElementRef, Html, Selector};
 
 pub trait HeaderValueExt {
     fn to_string(&self) -> String;
@@ -20,48 +19,11 @@ impl HeaderValueExt for HeaderValue {
     }
 }
 
-fn format_number(n: &str) -> String {
-    let number = n.replace(',', "").parse::<f64>().unwrap();
-    match number.abs() {
-        n if n >= 1_000_000.0 => format!("{:.2}m", number / 1_000_000.0),
-        n if (100_000.0..1_000_000.0).contains(&n) => format!("{:.1}k", number / 1_000.0),
-        n if n >= 1_000.0 => format!("{:.1}k", number / 1_000.0),
-        _ => format!("{:.0}", number),
-    }
-}
-
 fn selector(selector: impl Into<String>) -> scraper::Selector {
     Selector::parse(&selector.into()).unwrap()
 }
 
-fn starrize(rating: f32) -> String {
-    if rating == 0.0 {
-        return String::new();
-    }
-
-    let clean_rating = (rating * 2.0).round() / 2.0;
-
-    let rounded = clean_rating.floor() as usize;
-    let mut star_string = "<:lbstar:1061604009783341117>".repeat(rounded);
-
-    if clean_rating.fract() != 0.0 {
-        star_string += "<:lbhstar:1061603475949096991>";
-    }
-
-    star_string
-}
-
-fn convert_duration(minutes: u32) -> String {
-    let hours = minutes / 60;
-    let minutes = minutes % 60;
-    match (hours, minutes) {
-        (0, m) => format!("{}m", m),
-        (h, 0) => format!("{}h", h),
-        (h, m) => format!("{}h {}m", h, m),
-    }
-}
-
-fn format_bio(text: &str) -> String {
+pub fn format_bio(text: &str) -> String {
     let hyperlink_regex = build_regex(r#"<a.*href="(?P<link>.*?)".*>(?P<title>.*?)</a>"#);
     decode_html(
         hyperlink_regex
@@ -74,6 +36,8 @@ fn format_bio(text: &str) -> String {
             .replace("</b>", "**")
             .replace("<i>", "*")
             .replace("</i>", "*")
+            .replace("<em>", "*")
+            .replace("</em>", "*")
             .trim(),
     )
     .to_string()
@@ -82,109 +46,153 @@ fn format_bio(text: &str) -> String {
 fn build_regex(pat: &str) -> Regex {
     Regex::new(pat).unwrap()
 }
-pub fn get_diary(
-    username: String,
-) -> Result<(String, String, Vec<DiaryResult>), Box<dyn std::error::Error>> {
-    const BASE_URL: &str = "https://letterboxd.com";
-    let search_diary = reqget(format!("{}/{}/films/diary", BASE_URL, username))?.text()?;
-
-    if search_diary.contains("Sorry, we can’t find the page you’ve requested.") {
-        return Ok((
-            String::new(),
-            String::new(),
-            vec![DiaryResult {
-                found: false,
-                ..Default::default()
-            }],
-        ));
-    }
 
-    if search_diary.contains("No diary entries") {
-        return Ok((
-            String::new(),
-            String::new(),
-            vec![DiaryResult {
-                found: true,
-                title: "Not found".to_string(),
-                ..Default::default()
-            }],
-        ));
-    }
-
-    let sd_html = Html::parse_document(&search_diary);
-    let tbody_selector = selector("tbody");
-    let entries_selector = selector("tr");
-    let entries = sd_html
-        .select(&tbody_selector)
+fn select_one<'a>(html: &'a Html, pattern: &str) -> Result<ElementRef<'a>, ScrapeError> {
+    html.select(&selector(pattern))
         .next()
-        .unwrap()
-        .select(&entries_selector);
-    let mut diaries_vec: Vec<DiaryResult> = vec![];
-    let avatar_selector = selector(r#"img[width="24"]"#);
-    let avatar_raw = sd_html
-        .select(&avatar_selector)
+        .ok_or_else(|| ScrapeError::layout(pattern))
+}
+
+fn select_in<'a>(element: ElementRef<'a>, pattern: &str) -> Result<ElementRef<'a>, ScrapeError> {
+    element
+        .select(&selector(pattern))
         .next()
-        .unwrap()
+        .ok_or_else(|| ScrapeError::layout(pattern))
+}
+
+fn attr<'a>(element: ElementRef<'a>, name: &str) -> Result<&'a str, ScrapeError> {
+    element
         .value()
-        .attr("src")
-        .unwrap();
+        .attr(name)
+        .ok_or_else(|| ScrapeError::layout(format!("[{}]", name)))
+}
+
+fn is_not_found(page: &str) -> bool {
+    page.contains("Sorry, we can’t find the page you’ve requested.")
+}
+
+fn is_private(page: &str) -> bool {
+    page.contains("This profile is private")
+        || page.contains("hidden their activity from the public")
+}
+
+/// Parses the film search results page, best matches first.
+pub fn parse_search(page: &str) -> Result<Vec<FilmCandidate>, ScrapeError> {
+    let sf_html = Html::parse_document(page);
+    let title_selector = selector("span.film-title-wrapper > a");
+    let year_selector = selector("small.metadata a");
+    let directors_selector = selector("p.film-metadata a");
+    let mut candidates = vec![];
+    for result in sf_html.select(&selector("ul.results li")) {
+        let path = attr(select_in(result, "div")?, "data-target-link")?.to_string();
+        let title = match result.select(&title_selector).next() {
+            Some(title) => title.text().collect::<String>(),
+            None => attr(select_in(result, "img")?, "alt")?.to_string(),
+        };
+        let year = result
+            .select(&year_selector)
+            .next()
+            .and_then(|year| year.text().collect::<String>().trim().parse().ok());
+        let directors = result
+            .select(&directors_selector)
+            .map(|d| d.text().collect::<String>())
+            .collect();
+        candidates.push(FilmCandidate {
+            title: title.trim().to_string(),
+            year,
+            directors,
+            path,
+        });
+    }
+    if candidates.is_empty() {
+        return Err(ScrapeError::NotFound);
+    }
+    Ok(candidates)
+}
+
+/// Gets the `/film/<slug>/` path out of a film or review link, if `input` is one.
+pub fn film_path_from_url(input: &str) -> Option<String> {
+    let path_regex = build_regex(r#"letterboxd\.com/(?:[\w-]+/)?film/([\w-]+)"#);
+    path_regex
+        .captures(input)
+        .map(|caps| format!("/film/{}/", &caps[1]))
+}
+
+/// Gets `(username, slug)` out of a list link, if `input` is one.
+pub fn list_path_from_url(input: &str) -> Option<(String, String)> {
+    let path_regex = build_regex(r#"letterboxd\.com/([\w-]+)/list/([\w-]+)"#);
+    path_regex
+        .captures(input)
+        .map(|caps| (caps[1].to_string(), caps[2].to_string()))
+}
+
+/// Whether a paginated page links to a next one.
+pub fn has_next_page(page: &str) -> bool {
+    Html::parse_document(page)
+        .select(&selector("div.pagination a.next"))
+        .next()
+        .is_some()
+}
+
+/// Parses a `/<username>/films/diary` page into `(avatar, display name, entries)`.
+pub fn parse_diary(
+    page: &str,
+    base_url: &str,
+) -> Result<(String, String, Vec<DiaryResult>), ScrapeError> {
+    if is_private(page) {
+        return Err(ScrapeError::Private);
+    }
+
+    let sd_html = Html::parse_document(page);
+    let avatar_raw = attr(select_one(&sd_html, r#"img[width="24"]"#)?, "src")?;
     let avatar = if avatar_raw.contains("static") {
         String::new()
     } else {
         avatar_raw.replace("0-48-0-48", "0-220-0-220")
     };
-    let display_name_selector = selector(r#"meta[property="og:title"]"#);
-    let display_name = sd_html
-        .select(&display_name_selector)
-        .next()
-        .unwrap()
-        .value()
-        .attr("content")
-        .unwrap()
-        .split("’s")
-        .collect::<Vec<_>>()[0]
-        .to_string();
-    for entry in entries.take(5) {
-        let info_selector = selector(r#"a[class="edit-review-button has-icon icon-16 icon-edit"]"#);
-        let data = entry.select(&info_selector).next().unwrap().value();
+    let display_name = attr(
+        select_one(&sd_html, r#"meta[property="og:title"]"#)?,
+        "content",
+    )?
+    .split("’s")
+    .next()
+    .unwrap_or_default()
+    .to_string();
+
+    if page.contains("No diary entries") {
+        return Ok((avatar, display_name, vec![]));
+    }
+
+    let entries_selector = selector("tr");
+    let entries = select_one(&sd_html, "tbody")?.select(&entries_selector);
+    let mut diaries_vec: Vec<DiaryResult> = vec![];
+    for entry in entries {
+        let data = select_in(
+            entry,
+            r#"a[class="edit-review-button has-icon icon-16 icon-edit"]"#,
+        )?;
         let title = format!(
             "{} ({})",
-            data.attr("data-film-name").unwrap(),
-            data.attr("data-film-year").unwrap()
+            attr(data, "data-film-name")?,
+            attr(data, "data-film-year")?
         );
         let url = format!(
             "{}{}",
-            BASE_URL,
-            data.attr("data-film-poster")
-                .unwrap()
-                .replace("/image-150/", "")
+            base_url,
+            attr(data, "data-film-poster")?.replace("/image-150/", "")
         );
-        let date_raw = data.attr("data-viewing-date-str").unwrap().to_string();
-        let date = if date_raw.contains("2023") {
-            date_raw
-                .replace(" 2023", "")
-                .split(' ')
-                .collect::<Vec<_>>()
-                .into_iter()
-                .rev()
-                .collect::<Vec<_>>()
-                .join(" ")
-        } else {
-            let dates = date_raw.split(' ').collect::<Vec<_>>();
-            format!("{} {}, {}", dates[1], dates[0], dates[2])
-        };
-        let rating = starrize(data.attr("data-rating").unwrap().parse::<f32>().unwrap() / 2.0);
-        let reviewed = !data
-            .attr("data-review-text")
-            .unwrap()
-            .to_string()
-            .is_empty();
-        let rewatched: bool = data.attr("data-rewatch").unwrap().trim().parse().unwrap();
+        let date_raw = attr(data, "data-viewing-date-str")?;
+        let date = Date::parse_diary(date_raw)
+            .ok_or_else(|| ScrapeError::Parse(format!("diary date `{}`", date_raw)))?;
+        let stars = attr(data, "data-rating")?.parse::<f32>()? / 2.0;
+        let rating = starrize(stars);
+        let review = attr(data, "data-review-text")?.trim();
+        let reviewed = !review.is_empty();
+        let rewatched: bool = attr(data, "data-rewatch")?.trim().parse()?;
         let liked_selector =
             selector(r#"span[class="has-icon icon-16 large-liked icon-liked hide-for-owner"]"#);
         let liked = entry.select(&liked_selector).next().is_some();
         diaries_vec.push(DiaryResult {
-            found: true,
             title,
             rating,
             date,
@@ -192,211 +200,490 @@ pub fn get_diary(
             liked,
             reviewed,
             url,
+            review: reviewed.then(|| review.to_string()),
+            poster: None,
+            stars: (stars > 0.0).then_some(stars),
         });
     }
     Ok((avatar, display_name, diaries_vec))
 }
 
-pub fn get_film(title: &str) -> Result<FilmResult, Box<dyn std::error::Error>> {
-    const BASE_URL: &str = "https://letterboxd.com";
-    let title_regex = build_regex(
-        r#"([^[:ascii:][:alnum:]'\s]|^)([[:ascii:][:alnum:]'\s\u{4e00}-\u{9fff}]*)([^[:ascii:][:alnum:]'\s]|$)"#,
-    );
-    let new_title = title_regex
-        .replace_all(&title.to_lowercase(), "$2")
-        .to_string();
-    let search_film = reqget(BASE_URL.to_string() + "/search/films/" + &new_title + "/?adult")?;
-    let sf_ul = selector("ul.results");
-    let sf_li = selector("li");
-    let sf_div = selector("div");
-    let sf_html = Html::parse_document(&search_film.text()?);
-    let sf_ul2 = sf_html.select(&sf_ul).next().unwrap();
-    let film_url = sf_ul2
-        .select(&sf_li)
-        .next()
-        .unwrap()
-        .select(&sf_div)
-        .next()
-        .unwrap()
-        .value()
-        .attr("data-target-link")
-        .unwrap();
-    let film = reqget(BASE_URL.to_string() + film_url)?.text()?;
-    let info_film = reqget(BASE_URL.to_string() + film_url + "/reviews")?.text()?;
-    let html_film = Html::parse_document(&film);
-    let title_selector = selector(r#"meta[property="og:title"]"#);
-    let title = html_film
-        .select(&title_selector)
-        .next()
-        .unwrap()
-        .value()
-        .attr("content")
-        .unwrap();
-    let syn_selector = selector(r#"meta[name="description"]"#);
+/// Pulls the JSON-LD blob Letterboxd embeds in film pages, which is wrapped in CDATA comments.
+fn film_json_ld(html: &Html) -> Option<serde_json::Value> {
+    let script = html
+        .select(&selector(r#"script[type="application/ld+json"]"#))
+        .next()?
+        .text()
+        .collect::<String>();
+    let start = script.find('{')?;
+    let end = script.rfind('}')?;
+    serde_json::from_str(&script[start..=end]).ok()
+}
 
-    let syn = html_film.select(&syn_selector).next();
-    let synopsis_raw: String = if let Some(syno) = syn {
-        syno.value().attr("content").unwrap().to_string()
-    } else {
-        String::new()
-    };
-    let synopsis = if synopsis_raw.len() > 100 {
-        format!("{}...", &synopsis_raw[..100])
-    } else {
-        synopsis_raw
-    };
-    let tag_selector = selector("h4.tagline");
-    let tagline_check = html_film.select(&tag_selector).next();
-    let tagline = if let Some(tag) = tagline_check {
-        tag.inner_html()
-    } else {
-        "".to_string()
+fn parse_count(number: &str) -> Result<u64, ScrapeError> {
+    Ok(number.replace(',', "").trim().parse()?)
+}
+
+/// Parses a film page along with its `/reviews` page, which carries the watch stats.
+pub fn parse_film(film_html: &str, reviews_html: &str, url: &str) -> Result<Film, ScrapeError> {
+    let html_film = Html::parse_document(film_html);
+    let json_ld = film_json_ld(&html_film).unwrap_or_default();
+    let og_title = attr(
+        select_one(&html_film, r#"meta[property="og:title"]"#)?,
+        "content",
+    )?;
+    let year_regex = build_regex(r#"^(.*) \((\d{4})\)$"#);
+    let (title, year) = match year_regex.captures(og_title) {
+        Some(caps) => (caps[1].to_string(), caps[2].parse().ok()),
+        None => (og_title.to_string(), None),
     };
-    let poster_pattern = build_regex(r#""image":"([^\s"']+)"#);
-    let poster = if poster_pattern.captures(&film).is_some() {
-        poster_pattern
-            .captures(&film)
-            .unwrap()
-            .get(1)
-            .unwrap()
+    let year = year.or_else(|| {
+        json_ld["releasedEvent"][0]["startDate"]
             .as_str()
-    } else {
-        ""
-    };
-    let rating_selector = selector(r#"meta[name="twitter:data2"]"#);
-    let rating_point: f32 = if html_film.select(&rating_selector).next().is_some() {
-        html_film
-            .select(&rating_selector)
-            .next()
-            .unwrap()
-            .value()
-            .attr("content")
-            .unwrap()
-            .split(" out")
-            .next()
-            .unwrap()
-            .parse()
-            .unwrap()
-    } else {
-        0.0
-    };
-
-    let rating = format!(
-        "{} {}{}",
-        starrize(rating_point),
-        rating_point,
-        ["", ".0"][(rating_point.fract() == 0.0) as usize]
-    );
-    let directors_selector = selector(r#"meta[name="twitter:data1"]"#);
-    let directors = html_film
-        .select(&directors_selector)
+            .and_then(|y| y.parse().ok())
+    });
+    let slug = url
+        .trim_end_matches('/')
+        .rsplit('/')
         .next()
-        .unwrap()
-        .value()
-        .attr("content")
-        .unwrap()
+        .unwrap_or_default()
         .to_string();
-    let countries_pattern = build_regex(r#"/films/country/.*/" class=".*">(.*)</a>"#);
-    let countries_raw = countries_pattern.captures(&film);
-    let countries = if let Some(countries_raw) = countries_raw {
-        countries_raw
-            .get(0)
-            .map(|m| m.as_str())
-            .unwrap_or_default()
-            .split(r#"text-slug">"#)
-            .filter_map(|c| {
-                if c.contains("</a>") {
-                    Some(c.split("</a>").next().unwrap_or_default().to_string())
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>()
-            .join(", ")
-    } else {
-        String::new()
-    };
-    let duration_selector = selector(r#"p[class="text-link text-footer"]"#);
-    let duration_regex = build_regex(r#"(\d+)&nbsp;mins &nbsp;"#);
-    let duration_raw = html_film
-        .select(&duration_selector)
+    let synopsis = html_film
+        .select(&selector(r#"meta[name="description"]"#))
         .next()
-        .unwrap()
-        .inner_html();
-    let duration_str_raw = duration_regex.captures(&duration_raw);
-    let duration_str = if let Some(duration_str_raw) = duration_str_raw {
-        duration_str_raw.get(1).unwrap().as_str()
-    } else {
-        "0"
+        .and_then(|syn| syn.value().attr("content"))
+        .map(|syn| syn.to_string());
+    let tagline = html_film
+        .select(&selector("h4.tagline"))
+        .next()
+        .map(|tag| decode_html(&tag.inner_html()).to_string());
+    let poster = json_ld["image"].as_str().map(|p| p.to_string());
+    let average_rating = match html_film
+        .select(&selector(r#"meta[name="twitter:data2"]"#))
+        .next()
+    {
+        Some(rating) => Some(
+            attr(rating, "content")?
+                .split(" out")
+                .next()
+                .unwrap_or_default()
+                .parse::<f32>()?,
+        ),
+        None => None,
     };
-    let duration = convert_duration(duration_str.parse::<u32>().unwrap());
-    let genre_regex = build_regex(r#""genre":[\[](.*)"[\]]"#);
-    let genre_raw = genre_regex.captures(&film);
-    let genre = if let Some(genre_raw) = genre_raw {
-        genre_raw
-            .get(1)
-            .unwrap()
-            .as_str()
-            .replace('"', "")
-            .replace(',', ", ")
-    } else {
-        String::new()
+    let base_url = url.split("/film/").next().unwrap_or_default();
+    let mut directors = json_ld["director"]
+        .as_array()
+        .map(|directors| {
+            directors
+                .iter()
+                .filter_map(|d| {
+                    Some(Person {
+                        name: d["name"].as_str()?.to_string(),
+                        url: d["sameAs"].as_str().map(|path| format!("{}{}", base_url, path)),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if directors.is_empty() {
+        if let Some(names) = html_film
+            .select(&selector(r#"meta[name="twitter:data1"]"#))
+            .next()
+            .and_then(|d| d.value().attr("content"))
+        {
+            directors = names
+                .split(", ")
+                .map(|name| Person {
+                    name: name.to_string(),
+                    url: None,
+                })
+                .collect();
+        }
+    }
+    let countries = html_film
+        .select(&selector(r#"a[href^="/films/country/"]"#))
+        .map(|c| c.text().collect::<String>().trim().to_string())
+        .collect::<Vec<_>>();
+    let genres = json_ld["genre"]
+        .as_array()
+        .map(|genres| {
+            genres
+                .iter()
+                .filter_map(|g| g.as_str().map(|g| g.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let runtime_regex = build_regex(r#"(\d+)&nbsp;mins"#);
+    let runtime = match html_film
+        .select(&selector(r#"p[class="text-link text-footer"]"#))
+        .next()
+        .map(|footer| footer.inner_html())
+        .and_then(|footer| runtime_regex.captures(&footer).map(|c| c[1].to_string()))
+    {
+        Some(minutes) => Some(minutes.parse::<u32>()?),
+        None => None,
     };
-    let info_regex = build_regex(r#"title="(.*)&nbsp;(people|likes|reviews)"#);
-    let mut info: HashMap<String, String> = HashMap::new();
-    for i in info_regex.captures_iter(&info_film) {
-        info.insert(i[2].to_string(), format_number(&i[1]));
-    }
-    let result = FilmResult {
-        found: true,
-        title: title.to_string(),
+    let stats_regex = build_regex(r#"title="(.*)&nbsp;(people|likes|reviews)"#);
+    let mut stats = FilmStats::default();
+    for i in stats_regex.captures_iter(reviews_html) {
+        let count = parse_count(&i[1])?;
+        match &i[2] {
+            "people" => stats.watched = count,
+            "likes" => stats.likes = count,
+            _ => stats.reviews = count,
+        }
+    }
+    Ok(Film {
+        slug,
+        title,
+        year,
         tagline,
         synopsis,
-        rating,
-        duration,
+        average_rating,
+        runtime,
         directors,
+        genres,
         countries,
-        genre,
-        info,
-        poster: poster.to_string(),
-        url: BASE_URL.to_string() + film_url,
+        poster,
+        stats,
+        url: url.to_string(),
+    })
+}
+
+/// Parses a `/<username>/film/<slug>/` page, the member's latest entry of a film.
+pub fn parse_review(page: &str, username: &str, url: &str) -> Result<ReviewResult, ScrapeError> {
+    if is_private(page) {
+        return Err(ScrapeError::Private);
+    }
+    let html = Html::parse_document(page);
+    let title_wrapper = select_one(&html, "span.film-title-wrapper")?;
+    let title = select_in(title_wrapper, "a")?.text().collect::<String>();
+    let film = match title_wrapper.select(&selector("small.metadata a")).next() {
+        Some(year) => format!("{} ({})", title, year.text().collect::<String>()),
+        None => title,
+    };
+    let rated_regex = build_regex(r#"rated-(\d+)"#);
+    let stars = html
+        .select(&selector("span.rating"))
+        .next()
+        .and_then(|rating| rating.value().attr("class"))
+        .and_then(|class| rated_regex.captures(class))
+        .map(|caps| caps[1].parse::<f32>())
+        .transpose()?
+        .map(|rating| rating / 2.0)
+        .filter(|stars| *stars > 0.0);
+    let review = html.select(&selector("div.review")).next();
+    let spoilers = review.is_some_and(|review| {
+        review
+            .select(&selector("div.contains-spoilers"))
+            .next()
+            .is_some()
+    });
+    let text = review
+        .map(|review| {
+            review
+                .select(&selector("p"))
+                .filter(|p| {
+                    !p.ancestors()
+                        .filter_map(ElementRef::wrap)
+                        .any(|e| e.value().classes().any(|c| c == "contains-spoilers"))
+                })
+                .map(|p| format_bio(&p.inner_html()))
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        })
+        .unwrap_or_default();
+    let count_regex = build_regex(r#"([\d,]+)"#);
+    let count = |pattern: &str| -> Result<u64, ScrapeError> {
+        match html.select(&selector(pattern)).next() {
+            Some(element) => match count_regex.captures(&element.text().collect::<String>()) {
+                Some(caps) => parse_count(&caps[1]),
+                None => Ok(0),
+            },
+            None => Ok(0),
+        }
     };
-    Ok(result)
+    let poster = html
+        .select(&selector(r#"meta[property="og:image"]"#))
+        .next()
+        .and_then(|image| image.value().attr("content"))
+        .map(|image| image.to_string());
+    Ok(ReviewResult {
+        film,
+        username: username.to_string(),
+        rating: stars.map(starrize).unwrap_or_default(),
+        stars,
+        text,
+        spoilers,
+        likes: count("p.review-likes a")?,
+        comments: count("section#comments h2")?,
+        poster,
+        url: url.to_string(),
+    })
 }
 
-pub fn get_profile(username: &str) -> Result<ProfileResult, Box<dyn std::error::Error>> {
-    const BASE_URL: &str = "https://letterboxd.com";
-    let profile_url = format!("{}/{}", BASE_URL, username);
-    let search_profile = reqget(&profile_url)?.text()?;
+/// Parses a page of a list's `/detail/` view, the entries being only the ones of that page.
+pub fn parse_list(page: &str, url: &str) -> Result<ListResult, ScrapeError> {
+    let html = Html::parse_document(page);
+    let header = select_one(&html, "section.list-header")?;
+    let title = select_in(header, "h1")?
+        .text()
+        .collect::<String>()
+        .trim()
+        .to_string();
+    let author = select_in(header, "a.name")?
+        .text()
+        .collect::<String>()
+        .trim()
+        .to_string();
+    let description = header
+        .select(&selector("div.body-text"))
+        .next()
+        .map(|description| format_bio(&description.inner_html()))
+        .filter(|description| !description.is_empty());
+    let rank_selector = selector("p.list-number");
+    let year_selector = selector("small.metadata a");
+    let image_selector = selector("img");
+    let note_selector = selector("div.film-detail-content > div.body-text");
+    let mut entries = vec![];
+    for entry in html.select(&selector("li.film-detail")) {
+        let poster_div = select_in(entry, "div.film-poster")?;
+        let rank = match entry.select(&rank_selector).next() {
+            Some(rank) => Some(rank.text().collect::<String>().trim().parse::<u32>()?),
+            None => None,
+        };
+        let year = entry
+            .select(&year_selector)
+            .next()
+            .and_then(|year| year.text().collect::<String>().trim().parse().ok());
+        // Films without a poster get a placeholder, which isn't worth showing.
+        let poster = poster_div
+            .select(&image_selector)
+            .next()
+            .and_then(|img| img.value().attr("src"))
+            .filter(|src| !src.contains("empty-poster"))
+            .map(str::to_string);
+        let note = entry
+            .select(&note_selector)
+            .next()
+            .map(|note| format_bio(&note.inner_html()))
+            .filter(|note| !note.is_empty());
+        entries.push(ListEntry {
+            rank,
+            title: select_in(entry, "h2 > a")?.text().collect::<String>(),
+            year,
+            path: attr(poster_div, "data-target-link")?.to_string(),
+            poster,
+            note,
+        });
+    }
+    Ok(ListResult {
+        title,
+        description,
+        author,
+        ranked: entries.iter().any(|entry| entry.rank.is_some()),
+        entries,
+        url: url.to_string(),
+        truncated: false,
+    })
+}
 
-    if search_profile.contains("Sorry, we can’t find the page you’ve requested.") {
-        return Ok(ProfileResult {
-            found: false,
+/// Parses a page of a `/<username>/watchlist/` poster grid, the entries being only the ones
+/// of that page.
+pub fn parse_watchlist(page: &str, url: &str) -> Result<ListResult, ScrapeError> {
+    if is_private(page) {
+        return Err(ScrapeError::Private);
+    }
+    let html = Html::parse_document(page);
+    let title = attr(select_one(&html, r#"meta[property="og:title"]"#)?, "content")?;
+    let author = title.split("’s").next().unwrap_or_default().to_string();
+    let mut entries = vec![];
+    for poster in html.select(&selector("li.poster-container div.film-poster")) {
+        entries.push(ListEntry {
+            title: attr(select_in(poster, "img")?, "alt")?.to_string(),
+            path: attr(poster, "data-target-link")?.to_string(),
             ..Default::default()
         });
     }
+    Ok(ListResult {
+        title: format!("{}'s Watchlist", author),
+        author,
+        entries,
+        url: url.to_string(),
+        ..Default::default()
+    })
+}
 
-    let sp_html = Html::parse_document(&search_profile);
-    let username_selector = selector(r#"div[data-profile="true"]"#);
-    let actual_username = sp_html
-        .select(&username_selector)
-        .next()
-        .unwrap()
-        .value()
-        .attr("data-username")
-        .unwrap()
-        .to_string();
-    let name_selector = selector(r#"meta[property="og:title"]"#);
-    let name = sp_html
-        .select(&name_selector)
-        .next()
-        .unwrap()
-        .value()
-        .attr("content")
-        .unwrap()
-        .split("’s profile")
-        .collect::<Vec<_>>()[0]
-        .to_string();
+/// Parses a page of a `/<username>/films/ratings/` poster grid.
+pub fn parse_ratings(page: &str) -> Result<Vec<RatedFilm>, ScrapeError> {
+    if is_private(page) {
+        return Err(ScrapeError::Private);
+    }
+    let html = Html::parse_document(page);
+    let rating_selector = selector("span.rating");
+    let rated_regex = build_regex(r#"rated-(\d+)"#);
+    let mut ratings = vec![];
+    for film in html.select(&selector("li.poster-container")) {
+        let poster = select_in(film, "div.film-poster")?;
+        let Some(caps) = film
+            .select(&rating_selector)
+            .next()
+            .and_then(|rating| rating.value().attr("class"))
+            .and_then(|class| rated_regex.captures(class))
+        else {
+            continue;
+        };
+        ratings.push(RatedFilm {
+            title: attr(select_in(poster, "img")?, "alt")?.to_string(),
+            path: attr(poster, "data-target-link")?.to_string(),
+            stars: caps[1].parse::<f32>()? / 2.0,
+        });
+    }
+    Ok(ratings)
+}
+
+/// The entries of the first list that are in every other list too, in the first list's order.
+pub fn common_entries(lists: &[ListResult]) -> Vec<ListEntry> {
+    let Some((first, others)) = lists.split_first() else {
+        return vec![];
+    };
+    first
+        .entries
+        .iter()
+        .filter(|entry| {
+            others
+                .iter()
+                .all(|list| list.entries.iter().any(|other| other.path == entry.path))
+        })
+        .cloned()
+        .collect()
+}
+
+/// What a roulette film has to be like, every field being optional.
+#[derive(Clone, Debug, Default)]
+pub struct RouletteFilter {
+    pub genre: Option<String>,
+    /// The decade's first year, e.g. `1980`.
+    pub decade: Option<i32>,
+    /// Inclusive release years.
+    pub years: Option<(i32, i32)>,
+    pub country: Option<String>,
+    /// In minutes.
+    pub max_runtime: Option<u32>,
+    /// The least average rating out of 5.
+    pub min_rating: Option<f32>,
+}
+
+impl RouletteFilter {
+    pub fn is_empty(&self) -> bool {
+        self.genre.is_none()
+            && self.decade.is_none()
+            && self.years.is_none()
+            && self.country.is_none()
+            && self.max_runtime.is_none()
+            && self.min_rating.is_none()
+    }
+
+    /// The browse page listing the films that may match, `year` being one of `years`.
+    ///
+    /// Browse pages can't filter by runtime, so that's left to [`RouletteFilter::matches`].
+    pub fn browse_path(&self, year: Option<i32>) -> String {
+        let slug = |name: &str| name.trim().to_lowercase().replace(' ', "-");
+        let mut path = "/films/".to_string();
+        if let Some(genre) = &self.genre {
+            path.push_str(&format!("genre/{}/", slug(genre)));
+        }
+        if let Some(year) = year {
+            path.push_str(&format!("year/{}/", year));
+        } else if let Some(decade) = self.decade {
+            path.push_str(&format!("decade/{}s/", decade));
+        }
+        if let Some(country) = &self.country {
+            path.push_str(&format!("country/{}/", slug(country)));
+        }
+        // The best rated films come first, so the first pages are most likely to qualify.
+        let order = if self.min_rating.is_some() {
+            "rating"
+        } else {
+            "popular"
+        };
+        path.push_str(&format!("by/{}/", order));
+        path
+    }
+
+    /// Whether a browse page's `average_rating` is good enough, an unknown one being let
+    /// through until the film is looked up.
+    fn rated(&self, average_rating: Option<f32>) -> bool {
+        match (self.min_rating, average_rating) {
+            (Some(min_rating), Some(rating)) => rating >= min_rating,
+            _ => true,
+        }
+    }
+
+    /// Whether `film` has everything the browse page couldn't check.
+    pub fn matches(&self, film: &Film) -> bool {
+        let short = match self.max_runtime {
+            Some(max_runtime) => film.runtime.is_some_and(|runtime| runtime <= max_runtime),
+            None => true,
+        };
+        let rated = match self.min_rating {
+            Some(min_rating) => film.average_rating.is_some_and(|rating| rating >= min_rating),
+            None => true,
+        };
+        short && rated
+    }
+}
+
+/// Parses a browse page into `(path, average rating)` of its films.
+pub fn parse_browse(page: &str) -> Result<Vec<(String, Option<f32>)>, ScrapeError> {
+    let html = Html::parse_document(page);
+    let mut films = vec![];
+    for film in html.select(&selector("li.poster-container")) {
+        let path = attr(select_in(film, "div.film-poster")?, "data-target-link")?.to_string();
+        let rating = match film.value().attr("data-average-rating") {
+            Some(rating) => Some(rating.parse::<f32>()?),
+            None => None,
+        };
+        films.push((path, rating));
+    }
+    Ok(films)
+}
+
+/// The number of the last page a paginated page links to, `1` without pagination.
+pub fn last_page(page: &str) -> u32 {
+    Html::parse_document(page)
+        .select(&selector("div.paginate-pages a"))
+        .filter_map(|a| a.text().collect::<String>().trim().parse().ok())
+        .max()
+        .unwrap_or(1)
+}
+
+/// Parses a `/<username>` profile page.
+pub fn parse_profile(
+    page: &str,
+    base_url: &str,
+    url: &str,
+) -> Result<ProfileResult, ScrapeError> {
+    if is_private(page) {
+        return Err(ScrapeError::Private);
+    }
+
+    let sp_html = Html::parse_document(page);
+    let actual_username = attr(
+        select_one(&sp_html, r#"div[data-profile="true"]"#)?,
+        "data-username",
+    )?
+    .to_string();
+    let name = attr(
+        select_one(&sp_html, r#"meta[property="og:title"]"#)?,
+        "content",
+    )?
+    .split("’s profile")
+    .next()
+    .unwrap_or_default()
+    .to_string();
     let location_selector = selector(r#"div[class="metadatum -has-label js-metadatum"]"#);
     let location = sp_html
         .select(&location_selector)
@@ -411,49 +698,41 @@ pub fn get_profile(username: &str) -> Result<ProfileResult, Box<dyn std::error::
     let links_selector = selector(r#"a[class="metadatum -has-label js-metadatum"]"#);
     let websites = sp_html
         .select(&links_selector)
-        .map(|link| link.value().attr("href").unwrap().to_string())
+        .filter_map(|link| link.value().attr("href").map(|l| l.to_string()))
         .collect::<Vec<_>>();
-    let favorites_section_selector = selector(r#"section[id="favourites"]"#);
-    let favorites_section = sp_html.select(&favorites_section_selector).next().unwrap();
-    let favorites_selector = selector("div");
-    let favorites_links = favorites_section.select(&favorites_selector);
-    let mut favorites_link = vec![];
-    let description_selector = selector(r#"meta[name="description"]"#);
-    let description_raw = sp_html
-        .select(&description_selector)
-        .next()
-        .unwrap()
-        .value()
-        .attr("content")
-        .unwrap();
+    let description_raw = attr(
+        select_one(&sp_html, r#"meta[name="description"]"#)?,
+        "content",
+    )?;
     let mut favorites = String::new();
-    if description_raw.contains("Favorites: ") {
-        let description = description_raw.split("Favorites: ").collect::<Vec<_>>()[1];
+    let favorites_section_selector = selector(r#"section[id="favourites"]"#);
+    if let (Some(favorites_section), Some(description)) = (
+        sp_html.select(&favorites_section_selector).next(),
+        description_raw.split("Favorites: ").nth(1),
+    ) {
         let favorite_titles = if description.contains("Bio: ") {
-            description.split(". Bio: ").collect::<Vec<_>>()[0]
+            description
+                .split(". Bio: ")
+                .next()
+                .unwrap_or_default()
                 .split(", ")
                 .collect::<Vec<_>>()
         } else {
-            let new_description = &description[..description.len() - 1];
+            let new_description = description.strip_suffix('.').unwrap_or(description);
             new_description.split(", ").collect::<Vec<_>>()
         };
-        for favorite in favorites_links {
-            favorites_link
-                .push(BASE_URL.to_owned() + favorite.value().attr("data-film-slug").unwrap());
-        }
+        let favorites_selector = selector("div");
+        let favorites_link = favorites_section
+            .select(&favorites_selector)
+            .filter_map(|favorite| favorite.value().attr("data-film-slug"))
+            .map(|slug| base_url.to_string() + slug)
+            .collect::<Vec<_>>();
         for (link, title) in favorites_link.iter().zip(favorite_titles.iter()) {
             favorites.push_str(&format!("• [{}]({})\n", title, link));
         }
         favorites.pop();
     }
-    let avatar_selector = selector(r#"img[width="110"]"#);
-    let avatar_raw = sp_html
-        .select(&avatar_selector)
-        .next()
-        .unwrap()
-        .value()
-        .attr("src")
-        .unwrap();
+    let avatar_raw = attr(select_one(&sp_html, r#"img[width="110"]"#)?, "src")?;
     let avatar = if avatar_raw.contains("static") {
         String::new()
     } else {
@@ -463,43 +742,34 @@ pub fn get_profile(username: &str) -> Result<ProfileResult, Box<dyn std::error::
     let bio_raw = sp_html.select(&bio_selector).next();
     let medium_bio_selector = selector(r#"div[class="collapsible-text body-text -small"]"#);
     let short_bio_selector =
-        Selector::parse(r#"div[class="collapsible-text body-text -small js-bio-content"]"#)
-            .unwrap();
-    let bio = if let Some(raw) = bio_raw {
-        if let Some(div_bio) = raw.select(&bio_selector).next() {
-            format_bio(&div_bio.inner_html())
-        } else if let Some(medium_bio) = bio_raw
-            .as_ref()
-            .and_then(|raw| raw.select(&medium_bio_selector).next())
-        {
-            format_bio(&medium_bio.inner_html())
-        } else if let Some(short_bio) = sp_html.select(&short_bio_selector).next() {
-            format_bio(&short_bio.inner_html())
-        } else {
-            String::new()
-        }
-    } else {
-        String::new()
-    };
+        selector(r#"div[class="collapsible-text body-text -small js-bio-content"]"#);
+    // Short bios aren't collapsed, so they have to be looked up on their own.
+    let bio = bio_raw
+        .and_then(|raw| {
+            raw.select(&bio_selector)
+                .next()
+                .or_else(|| raw.select(&medium_bio_selector).next())
+        })
+        .or_else(|| sp_html.select(&short_bio_selector).next())
+        .map(|bio| format_bio(&bio.inner_html()))
+        .unwrap_or_default();
     let data_selector = selector(r#"h4[class="profile-statistic statistic"]"#);
     let mut films = sp_html.select(&data_selector);
     let mut films_count = String::new();
     for film in films.by_ref().take(2) {
+        let count = film.text().next().unwrap_or_default();
         if films_count.is_empty() {
-            films_count.push_str(&format!(
-                "{} films logged, ",
-                film.text().collect::<Vec<_>>()[0]
-            ))
+            films_count.push_str(&format!("{} films logged, ", count))
         } else {
-            films_count.push_str(&format!(
-                "{} this year.",
-                film.text().collect::<Vec<_>>()[0]
-            ))
+            films_count.push_str(&format!("{} this year.", count))
         }
     }
-    let followers = films.last().unwrap().text().collect::<Vec<_>>()[0].to_string();
+    let followers = films
+        .last()
+        .and_then(|f| f.text().next())
+        .ok_or_else(|| ScrapeError::layout(r#"h4[class="profile-statistic statistic"]"#))?
+        .to_string();
     Ok(ProfileResult {
-        found: true,
         avatar,
         bio,
         username: actual_username,
@@ -509,72 +779,730 @@ pub fn get_profile(username: &str) -> Result<ProfileResult, Box<dyn std::error::
         location,
         films_count,
         websites,
-        url: profile_url,
+        url: url.to_string(),
     })
 }
 
-fn generate_lbxd_link() -> String {
-    let mut rng = rand::thread_rng();
-    let x: Vec<char> = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"
-        .chars()
-        .collect();
-    format!(
-        "https://boxd.it/{}",
-        (0..6)
-            .map(|_| x[rng.gen_range(0..x.len())])
-            .collect::<String>()
-    )
+/// Async Letterboxd scraper sharing one pooled `reqwest::Client`.
+#[derive(Clone, Debug)]
+pub struct LetterboxdClient {
+    http: reqwest::Client,
+    base_url: String,
 }
 
-pub fn get_roulette() -> Result<FilmResult, Box<dyn std::error::Error>> {
-    let mut url = generate_lbxd_link();
-    let mut hd;
-    let res = loop {
-        let c = url.clone();
-        if url[url.len() - 4..].contains('/') {
-            url = generate_lbxd_link()
+impl Default for LetterboxdClient {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// Builder for [`LetterboxdClient`], mainly so tests can point it at a mock server.
+#[derive(Debug)]
+pub struct LetterboxdClientBuilder {
+    base_url: String,
+    user_agent: String,
+}
+
+impl LetterboxdClientBuilder {
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn build(self) -> LetterboxdClient {
+        let http = reqwest::Client::builder()
+            .user_agent(self.user_agent)
+            .pool_idle_timeout(std::time::Duration::from_secs(90))
+            .build()
+            .expect("failed to build the Letterboxd HTTP client");
+        LetterboxdClient {
+            http,
+            base_url: self.base_url,
+        }
+    }
+}
+
+impl LetterboxdClient {
+    pub fn builder() -> LetterboxdClientBuilder {
+        LetterboxdClientBuilder {
+            base_url: config::LETTERBOXD_BASE_URL.to_string(),
+            user_agent: config::USER_AGENT.to_string(),
         }
-        let cc = c.clone();
-        dbg!(&url[url.len() - 4..]);
-        if let Ok(res) = reqget(c) {
-            hd = res.headers().clone();
-            if ["Film", "LogEntry"].contains(
-                &hd.get("x-letterboxd-type")
-                    .map(|h| h.to_string())
-                    .unwrap_or_else(|| "X".to_string())
-                    .as_str(),
-            ) {
-                break res;
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn fetch(&self, url: &str) -> Result<String, ScrapeError> {
+        let res = self.http.get(url).send().await?;
+        match res.status() {
+            reqwest::StatusCode::NOT_FOUND => return Err(ScrapeError::NotFound),
+            reqwest::StatusCode::TOO_MANY_REQUESTS => return Err(ScrapeError::RateLimited),
+            _ => {}
+        }
+        let page = res.error_for_status()?.text().await?;
+        if is_not_found(&page) {
+            return Err(ScrapeError::NotFound);
+        }
+        Ok(page)
+    }
+
+    /// Follows a `boxd.it` link to the Letterboxd page it points at.
+    pub async fn resolve_short_link(&self, url: &str) -> Result<String, ScrapeError> {
+        let res = self.http.get(url).send().await?;
+        match res.status() {
+            reqwest::StatusCode::NOT_FOUND => Err(ScrapeError::NotFound),
+            reqwest::StatusCode::TOO_MANY_REQUESTS => Err(ScrapeError::RateLimited),
+            _ => Ok(res.error_for_status()?.url().to_string()),
+        }
+    }
+
+    /// Gets the recent diary entries as `(avatar, display name, entries)`.
+    ///
+    /// The RSS feed is tried first since it's sturdier, but it has no avatar (so it's `None`)
+    /// and only covers recent activity, so the diary page is scraped when it fails or has no
+    /// diary entries.
+    pub async fn get_diary(
+        &self,
+        username: &str,
+    ) -> Result<(Option<String>, String, Vec<DiaryResult>), ScrapeError> {
+        match self.get_diary_feed(username).await {
+            Ok((display_name, mut entries)) if !entries.is_empty() => {
+                entries.truncate(5);
+                return Ok((None, display_name, entries));
+            }
+            Ok(_) => {}
+            Err(why @ (ScrapeError::NotFound | ScrapeError::RateLimited)) => return Err(why),
+            Err(why) => eprintln!("Couldn't read `{}`'s diary feed: {}", username, why),
+        }
+        let mut diary = self.get_diary_page(username, None, 1).await?;
+        diary.entries.truncate(5);
+        let avatar = Some(diary.avatar).filter(|avatar| !avatar.is_empty());
+        Ok((avatar, diary.display_name, diary.entries))
+    }
+
+    /// Gets page `page` of a diary, `period` being like `2024` or `2024/05` to only get the
+    /// entries of that year or month.
+    pub async fn get_diary_page(
+        &self,
+        username: &str,
+        period: Option<&str>,
+        page: u32,
+    ) -> Result<DiaryPage, ScrapeError> {
+        let mut url = format!("{}/{}/films/diary/", self.base_url, username);
+        if let Some(period) = period {
+            url.push_str(&format!("for/{}/", period));
+        }
+        if page > 1 {
+            url.push_str(&format!("page/{}/", page));
+        }
+        let search_diary = self.fetch(&url).await?;
+        let (avatar, display_name, entries) = parse_diary(&search_diary, &self.base_url)?;
+        Ok(DiaryPage {
+            avatar,
+            display_name,
+            entries,
+            has_next: has_next_page(&search_diary),
+        })
+    }
+
+    /// Gets the diary entries of the `/<username>/rss/` feed as `(display name, entries)`.
+    pub async fn get_diary_feed(
+        &self,
+        username: &str,
+    ) -> Result<(String, Vec<DiaryResult>), ScrapeError> {
+        let feed = self
+            .fetch(&format!("{}/{}/rss/", self.base_url, username))
+            .await?;
+        rss::parse_diary_feed(&feed, &self.base_url)
+    }
+
+    pub async fn search_films(&self, title: &str) -> Result<Vec<FilmCandidate>, ScrapeError> {
+        let title_regex = build_regex(
+            r#"([^[:ascii:][:alnum:]'\s]|^)([[:ascii:][:alnum:]'\s\u{4e00}-\u{9fff}]*)([^[:ascii:][:alnum:]'\s]|$)"#,
+        );
+        let new_title = title_regex
+            .replace_all(&title.to_lowercase(), "$2")
+            .to_string();
+        let search_film = self
+            .fetch(&format!("{}/search/films/{}/?adult", self.base_url, new_title))
+            .await?;
+        parse_search(&search_film)
+    }
+
+    /// Gets a film by its Letterboxd path, e.g. `/film/alien/`.
+    pub async fn get_film_at(&self, film_url: &str) -> Result<Film, ScrapeError> {
+        let film = self.fetch(&format!("{}{}", self.base_url, film_url)).await?;
+        let info_film = self
+            .fetch(&format!("{}{}reviews", self.base_url, film_url))
+            .await?;
+        parse_film(&film, &info_film, &format!("{}{}", self.base_url, film_url))
+    }
+
+    /// Gets `username`'s latest entry of the film at `film_path`, e.g. `/film/alien/`.
+    pub async fn get_review(
+        &self,
+        username: &str,
+        film_path: &str,
+    ) -> Result<ReviewResult, ScrapeError> {
+        let url = format!("{}/{}{}", self.base_url, username, film_path);
+        let page = self.fetch(&url).await?;
+        parse_review(&page, username, &url)
+    }
+
+    /// Fetches the list-like pages starting at `page_url`, up to `max_pages` of them, into one
+    /// list whose link is `url`.
+    async fn get_pages(
+        &self,
+        page_url: &str,
+        url: &str,
+        max_pages: u32,
+        parse: fn(&str, &str) -> Result<ListResult, ScrapeError>,
+    ) -> Result<ListResult, ScrapeError> {
+        let first_page = self.fetch(page_url).await?;
+        let mut list = parse(&first_page, url)?;
+        let mut has_next = has_next_page(&first_page);
+        let mut page = 1;
+        while has_next {
+            if page == max_pages {
+                list.truncated = true;
+                break;
+            }
+            page += 1;
+            let next_page = self
+                .fetch(&format!("{}page/{}/", page_url, page))
+                .await?;
+            list.entries.extend(parse(&next_page, url)?.entries);
+            has_next = has_next_page(&next_page);
+        }
+        Ok(list)
+    }
+
+    /// Gets `username`'s list `slug` with the entries of up to [`config::LIST_MAX_PAGES`]
+    /// pages.
+    pub async fn get_list(&self, username: &str, slug: &str) -> Result<ListResult, ScrapeError> {
+        let url = format!("{}/{}/list/{}/", self.base_url, username, slug);
+        // The detail view is the only one with the notes.
+        self.get_pages(
+            &format!("{}detail/", url),
+            &url,
+            config::LIST_MAX_PAGES,
+            parse_list,
+        )
+        .await
+    }
+
+    /// Gets `username`'s watchlist with the entries of up to
+    /// [`config::WATCHLIST_MAX_PAGES`] pages.
+    pub async fn get_watchlist(&self, username: &str) -> Result<ListResult, ScrapeError> {
+        let url = format!("{}/{}/watchlist/", self.base_url, username);
+        self.get_pages(&url, &url, config::WATCHLIST_MAX_PAGES, parse_watchlist)
+            .await
+    }
+
+    /// Gets every film `username` rated, up to [`config::RATINGS_MAX_PAGES`] pages of them.
+    pub async fn get_ratings(&self, username: &str) -> Result<Vec<RatedFilm>, ScrapeError> {
+        let url = format!("{}/{}/films/ratings/", self.base_url, username);
+        let mut ratings = vec![];
+        for page in 1..=config::RATINGS_MAX_PAGES {
+            let page_url = if page == 1 {
+                url.clone()
             } else {
-                url.pop();
+                format!("{}page/{}/", url, page)
+            };
+            let ratings_page = self.fetch(&page_url).await?;
+            ratings.extend(parse_ratings(&ratings_page)?);
+            if !has_next_page(&ratings_page) {
+                break;
             }
         }
-    };
-    let res_html = Html::parse_document(&res.text()?);
-    let header = hd.get("x-letterboxd-type").unwrap().to_str().unwrap();
-    let title = if header == "Film" {
-        let title_selector = selector(r#"meta[property="og:title"]"#);
-        res_html
-            .select(&title_selector)
-            .next()
+        Ok(ratings)
+    }
+
+    pub async fn get_profile(&self, username: &str) -> Result<ProfileResult, ScrapeError> {
+        let profile_url = format!("{}/{}", self.base_url, username);
+        let search_profile = self.fetch(&profile_url).await?;
+        parse_profile(&search_profile, &self.base_url, &profile_url)
+    }
+
+    /// Gets a random film matching `filter` off a random browse page, `NotFound` meaning
+    /// none of the films looked up matched. An empty filter draws from every popular film.
+    pub async fn get_roulette(&self, filter: &RouletteFilter) -> Result<Film, ScrapeError> {
+        let year = filter
+            .years
+            .map(|(from, to)| rand::thread_rng().gen_range(from..=to));
+        let url = format!("{}{}", self.base_url, filter.browse_path(year));
+        let first_page = self.fetch(&url).await?;
+        let last = last_page(&first_page).min(config::ROULETTE_MAX_PAGE);
+        let page = rand::thread_rng().gen_range(1..=last);
+        let browse_page = if page == 1 {
+            first_page
+        } else {
+            self.fetch(&format!("{}page/{}/", url, page)).await?
+        };
+        let mut films = parse_browse(&browse_page)?;
+        films.retain(|(_, rating)| filter.rated(*rating));
+        films.shuffle(&mut rand::thread_rng());
+        for (path, _) in films.iter().take(config::ROULETTE_MAX_LOOKUPS) {
+            // One broken film page shouldn't spoil the spin, running out of requests should.
+            let film = match self.get_film_at(path).await {
+                Ok(film) => film,
+                Err(ScrapeError::RateLimited) => return Err(ScrapeError::RateLimited),
+                Err(_) => continue,
+            };
+            if filter.matches(&film) {
+                return Ok(film);
+            }
+        }
+        Err(ScrapeError::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_URL: &str = "https://letterboxd.com";
+    const STAR: &str = "<:lbstar:1061604009783341117>";
+    const HALF_STAR: &str = "<:lbhstar:1061603475949096991>";
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            include_str!(concat!("../../fixtures/letterboxd/", $name))
+        };
+    }
+
+    #[test]
+    fn diary_entries() {
+        let (avatar, display_name, entries) =
+            parse_diary(fixture!("diary.html"), BASE_URL).unwrap();
+        assert_eq!(
+            avatar,
+            "https://a.ltrbxd.com/resized/avatar/upload/1/2/3/4/shard/avtr-0-220-0-220-crop.jpg"
+        );
+        assert_eq!(display_name, "Jane Doe");
+        assert_eq!(entries.len(), 6);
+
+        let alien = &entries[0];
+        assert_eq!(alien.title, "Alien (1979)");
+        assert_eq!(alien.url, "https://letterboxd.com/film/alien");
+        assert_eq!(alien.date, Date::new(2023, 3, 14).unwrap());
+        assert_eq!(alien.rating, format!("{}{}", STAR.repeat(4), HALF_STAR));
+        assert_eq!(alien.stars, Some(4.5));
+        assert!(alien.liked && alien.rewatched && alien.reviewed);
+
+        let heat = &entries[1];
+        assert_eq!(heat.title, "Heat (1995)");
+        assert_eq!(heat.date, Date::new(2022, 1, 2).unwrap());
+        assert_eq!(heat.rating, "");
+        assert_eq!(heat.stars, None);
+        assert!(!heat.liked && !heat.rewatched && !heat.reviewed);
+
+        assert_eq!(entries[2].title, "Paris, Texas (1984)");
+        assert_eq!(entries[2].rating, STAR.repeat(5));
+    }
+
+    #[test]
+    fn diary_pagination() {
+        assert!(has_next_page(fixture!("diary.html")));
+        assert!(!has_next_page(fixture!("diary_no_avatar.html")));
+    }
+
+    #[test]
+    fn diary_without_avatar() {
+        let (avatar, display_name, entries) =
+            parse_diary(fixture!("diary_no_avatar.html"), BASE_URL).unwrap();
+        assert!(avatar.is_empty());
+        assert_eq!(display_name, "newbie");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].rating, STAR.repeat(3));
+    }
+
+    #[test]
+    fn diary_without_entries() {
+        let (avatar, display_name, entries) =
+            parse_diary(fixture!("diary_empty.html"), BASE_URL).unwrap();
+        assert!(!avatar.is_empty());
+        assert_eq!(display_name, "Jane Doe");
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn not_found_page() {
+        assert!(is_not_found(fixture!("not_found.html")));
+        assert!(!is_not_found(fixture!("diary.html")));
+    }
+
+    #[test]
+    fn search_results() {
+        let candidates = parse_search(fixture!("search.html")).unwrap();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].path, "/film/the-matrix/");
+        assert_eq!(candidates[0].display_title(), "The Matrix (1999)");
+        assert_eq!(
+            candidates[0].directors,
+            vec!["Lana Wachowski", "Lilly Wachowski"]
+        );
+        assert_eq!(candidates[1].title, "The Matrix Reloaded");
+        assert_eq!(candidates[1].year, Some(2003));
+        assert!(matches!(
+            parse_search(fixture!("search_empty.html")),
+            Err(ScrapeError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn film_with_multiple_directors() {
+        let film = parse_film(
+            fixture!("film.html"),
+            fixture!("film_reviews.html"),
+            "https://letterboxd.com/film/the-matrix/",
+        )
+        .unwrap();
+        assert_eq!(film.slug, "the-matrix");
+        assert_eq!(film.title, "The Matrix");
+        assert_eq!(film.year, Some(1999));
+        assert_eq!(film.display_title(), "The Matrix (1999)");
+        assert_eq!(film.tagline.as_deref(), Some("Believe the unbelievable."));
+        assert!(film
+            .synopsis
             .unwrap()
-            .value()
-            .attr("content")
+            .starts_with("Set in the 22nd century"));
+        assert_eq!(film.average_rating, Some(4.22));
+        assert_eq!(
+            film.directors
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Lana Wachowski", "Lilly Wachowski"]
+        );
+        assert_eq!(
+            film.directors[0].url.as_deref(),
+            Some("https://letterboxd.com/director/lana-wachowski/")
+        );
+        assert_eq!(film.countries, vec!["Australia", "USA"]);
+        assert_eq!(film.genres, vec!["Action", "Science Fiction"]);
+        assert_eq!(film.runtime, Some(136));
+        assert!(film
+            .poster
             .unwrap()
-    } else {
-        let title_selector = selector(r#"meta[property="og:title"]"#);
-        let rating = res_html
-            .select(&title_selector)
-            .next()
+            .ends_with("51518-the-matrix-0-230-0-345-crop.jpg"));
+        assert_eq!(film.stats.watched, 2_345_678);
+        assert_eq!(film.stats.likes, 654_321);
+        assert_eq!(film.stats.reviews, 1_234);
+    }
+
+    #[test]
+    fn film_with_missing_details() {
+        let film = parse_film(
+            fixture!("film_minimal.html"),
+            fixture!("film_reviews_empty.html"),
+            "https://letterboxd.com/film/untitled-short/",
+        )
+        .unwrap();
+        assert_eq!(film.title, "Untitled Short");
+        assert_eq!(film.year, Some(2024));
+        assert_eq!(film.directors.len(), 1);
+        assert_eq!(film.directors[0].name, "Jane Doe");
+        assert!(film.directors[0].url.is_none());
+        assert!(film.average_rating.is_none());
+        assert!(film.runtime.is_none());
+        assert!(film.tagline.is_none());
+        assert!(film.synopsis.is_none());
+        assert!(film.countries.is_empty());
+        assert!(film.genres.is_empty());
+        assert!(film.poster.is_none());
+        assert_eq!(film.stats.watched, 0);
+    }
+
+    #[test]
+    fn full_profile() {
+        let profile = parse_profile(
+            fixture!("profile.html"),
+            BASE_URL,
+            "https://letterboxd.com/janedoe",
+        )
+        .unwrap();
+        assert_eq!(profile.username, "janedoe");
+        assert_eq!(profile.name, "Jane Doe");
+        assert_eq!(profile.location, "📍 ***Jakarta, Indonesia***");
+        assert_eq!(
+            profile.websites,
+            vec!["https://janedoe.example.com", "https://twitter.com/janedoe"]
+        );
+        assert_eq!(
+            profile.favorites,
+            "• [The Matrix (1999)](https://letterboxd.com/film/the-matrix/)\n\
+             • [Heat (1995)](https://letterboxd.com/film/heat-1995/)"
+        );
+        assert_eq!(
+            profile.bio,
+            "Film nerd from **Jakarta**.\nCheck out [my blog](https://janedoe.example.com) & more."
+        );
+        assert!(profile.avatar.ends_with("avtr-0-220-0-220-crop.jpg"));
+        assert_eq!(profile.films_count, "1,024 films logged, 87 this year.");
+        assert_eq!(profile.followers, "1,337");
+    }
+
+    #[test]
+    fn bare_profile() {
+        let profile = parse_profile(
+            fixture!("profile_bare.html"),
+            BASE_URL,
+            "https://letterboxd.com/newbie",
+        )
+        .unwrap();
+        assert_eq!(profile.username, "newbie");
+        assert_eq!(profile.name, "newbie");
+        assert!(profile.avatar.is_empty());
+        assert!(profile.bio.is_empty());
+        assert!(profile.favorites.is_empty());
+        assert!(profile.location.is_empty());
+        assert!(profile.websites.is_empty());
+        assert_eq!(profile.films_count, "3 films logged, 3 this year.");
+        assert_eq!(profile.followers, "1");
+    }
+
+    #[test]
+    fn short_bio_profile() {
+        let profile = parse_profile(
+            fixture!("profile_short_bio.html"),
+            BASE_URL,
+            "https://letterboxd.com/shorty",
+        )
+        .unwrap();
+        assert_eq!(profile.bio, "Mostly horror. filmbro-k3x9qz");
+    }
+
+    #[test]
+    fn film_paths() {
+        assert_eq!(
+            film_path_from_url("https://letterboxd.com/film/the-matrix/").as_deref(),
+            Some("/film/the-matrix/")
+        );
+        assert_eq!(
+            film_path_from_url("letterboxd.com/janedoe/film/heat-1995/1/").as_deref(),
+            Some("/film/heat-1995/")
+        );
+        assert_eq!(film_path_from_url("the matrix"), None);
+    }
+
+    #[test]
+    fn ranked_list() {
+        let list = parse_list(
+            fixture!("list.html"),
+            "https://letterboxd.com/janedoe/list/best-of-the-80s/",
+        )
+        .unwrap();
+        assert_eq!(list.title, "Best of the 80s");
+        assert_eq!(list.author, "Jane Doe");
+        assert_eq!(
+            list.description.as_deref(),
+            Some("My **favourite** decade, ranked.\n\nStill & forever.")
+        );
+        assert!(list.ranked);
+        assert!(!list.truncated);
+        assert_eq!(list.entries.len(), 3);
+
+        let blade_runner = &list.entries[0];
+        assert_eq!(blade_runner.rank, Some(1));
+        assert_eq!(blade_runner.display_title(), "Blade Runner (1982)");
+        assert_eq!(blade_runner.path, "/film/blade-runner/");
+        assert_eq!(blade_runner.note.as_deref(), Some("Tears in *rain*."));
+        assert!(blade_runner
+            .poster
+            .as_deref()
             .unwrap()
-            .value()
-            .attr("content")
-            .unwrap();
-        if rating.contains("entry for") {
-            rating.split("entry for ").collect::<Vec<_>>()[1]
-        } else {
-            rating.split("review of ").collect::<Vec<_>>()[1]
-        }
-    };
-    get_film(title)
+            .ends_with("51683-blade-runner-0-150-0-225-crop.jpg"));
+
+        assert_eq!(list.entries[1].title, "Paris, Texas");
+        assert!(list.entries[1].note.is_none());
+        assert_eq!(list.entries[2].rank, Some(3));
+        assert!(list.entries[2].poster.is_none());
+    }
+
+    #[test]
+    fn unranked_list() {
+        let list = parse_list(
+            fixture!("list_unranked.html"),
+            "https://letterboxd.com/newbie/list/to-watch-someday/",
+        )
+        .unwrap();
+        assert_eq!(list.title, "to watch someday");
+        assert_eq!(list.author, "newbie");
+        assert!(list.description.is_none());
+        assert!(!list.ranked);
+        assert_eq!(list.entries.len(), 1);
+        assert_eq!(list.entries[0].display_title(), "Untitled Short");
+        assert_eq!(list.entries[0].rank, None);
+        assert_eq!(list.entries[0].note.as_deref(), Some("A friend's."));
+        assert!(!has_next_page(fixture!("list_unranked.html")));
+    }
+
+    #[test]
+    fn watchlist_page() {
+        let watchlist = parse_watchlist(
+            fixture!("watchlist.html"),
+            "https://letterboxd.com/janedoe/watchlist/",
+        )
+        .unwrap();
+        assert_eq!(watchlist.title, "Jane Doe's Watchlist");
+        assert_eq!(watchlist.author, "Jane Doe");
+        assert!(!watchlist.ranked);
+        assert_eq!(
+            watchlist
+                .entries
+                .iter()
+                .map(|e| e.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/film/alien/", "/film/blade-runner/", "/film/paris-texas/"]
+        );
+        assert_eq!(watchlist.entries[2].title, "Paris, Texas");
+        assert!(has_next_page(fixture!("watchlist.html")));
+    }
+
+    #[test]
+    fn rated_films() {
+        let ratings = parse_ratings(fixture!("ratings.html")).unwrap();
+        assert_eq!(ratings.len(), 2);
+        assert_eq!(ratings[0].title, "Alien");
+        assert_eq!(ratings[0].path, "/film/alien/");
+        assert_eq!(ratings[0].stars, 5.0);
+        assert_eq!(ratings[1].stars, 3.5);
+        assert!(has_next_page(fixture!("ratings.html")));
+    }
+
+    #[test]
+    fn browse_paths() {
+        let filter = RouletteFilter {
+            genre: Some("Science Fiction".to_string()),
+            decade: Some(1980),
+            country: Some("USA".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.browse_path(None),
+            "/films/genre/science-fiction/decade/1980s/country/usa/by/popular/"
+        );
+        let filter = RouletteFilter {
+            years: Some((1975, 1985)),
+            min_rating: Some(4.0),
+            ..Default::default()
+        };
+        assert_eq!(filter.browse_path(Some(1979)), "/films/year/1979/by/rating/");
+        assert!(RouletteFilter::default().is_empty());
+    }
+
+    #[test]
+    fn browse_page() {
+        let films = parse_browse(fixture!("browse.html")).unwrap();
+        assert_eq!(
+            films,
+            vec![
+                ("/film/the-thing/".to_string(), Some(3.91)),
+                ("/film/the-shining/".to_string(), Some(4.2)),
+                ("/film/untitled-short/".to_string(), None),
+            ]
+        );
+        assert_eq!(last_page(fixture!("browse.html")), 41);
+        assert_eq!(last_page(fixture!("watchlist.html")), 1);
+    }
+
+    #[test]
+    fn roulette_filter() {
+        let filter = RouletteFilter {
+            max_runtime: Some(120),
+            min_rating: Some(4.0),
+            ..Default::default()
+        };
+        let film = |runtime, average_rating| Film {
+            runtime: Some(runtime),
+            average_rating,
+            ..Default::default()
+        };
+        assert!(filter.matches(&film(119, Some(4.2))));
+        assert!(!filter.matches(&film(121, Some(4.2))));
+        assert!(!filter.matches(&film(90, Some(3.9))));
+        assert!(!filter.matches(&film(90, None)));
+        assert!(filter.rated(None));
+    }
+
+    #[test]
+    fn common_watchlist_entries() {
+        let watchlist = |paths: &[&str]| ListResult {
+            entries: paths
+                .iter()
+                .map(|path| ListEntry {
+                    path: path.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let lists = vec![
+            watchlist(&["/film/alien/", "/film/heat-1995/", "/film/jaws/"]),
+            watchlist(&["/film/jaws/", "/film/alien/"]),
+            watchlist(&["/film/alien/", "/film/jaws/", "/film/heat-1995/"]),
+        ];
+        let common = common_entries(&lists)
+            .into_iter()
+            .map(|e| e.path)
+            .collect::<Vec<_>>();
+        assert_eq!(common, vec!["/film/alien/", "/film/jaws/"]);
+        assert_eq!(common_entries(&lists[..1]).len(), 3);
+        assert!(common_entries(&[]).is_empty());
+    }
+
+    #[test]
+    fn list_paths() {
+        assert_eq!(
+            list_path_from_url("https://letterboxd.com/janedoe/list/best-of-the-80s/detail/"),
+            Some(("janedoe".to_string(), "best-of-the-80s".to_string()))
+        );
+        assert_eq!(list_path_from_url("best-of-the-80s"), None);
+    }
+
+    #[test]
+    fn review_with_spoilers() {
+        let review = parse_review(
+            fixture!("review.html"),
+            "janedoe",
+            "https://letterboxd.com/janedoe/film/alien/",
+        )
+        .unwrap();
+        assert_eq!(review.film, "Alien (1979)");
+        assert_eq!(review.stars, Some(4.5));
+        assert!(review.spoilers);
+        assert_eq!(
+            review.text,
+            "Perfect. **Still** the best & scariest.\n\n\
+             The *chestburster* scene, [Aliens](https://letterboxd.com/film/aliens/) never topped it."
+        );
+        assert_eq!(review.likes, 1204);
+        assert_eq!(review.comments, 3);
+        assert!(review.poster.unwrap().ends_with("51714-alien-0-1000-0-1500-crop.jpg"));
+    }
+
+    #[test]
+    fn entry_without_review() {
+        let review = parse_review(
+            fixture!("review_plain.html"),
+            "janedoe",
+            "https://letterboxd.com/janedoe/film/heat-1995/",
+        )
+        .unwrap();
+        assert_eq!(review.film, "Heat (1995)");
+        assert_eq!(review.stars, None);
+        assert_eq!(review.rating, "");
+        assert!(!review.spoilers);
+        assert!(review.text.is_empty());
+        assert_eq!(review.likes, 0);
+        assert_eq!(review.comments, 0);
+    }
 }
@@ -1,4 +1,17 @@
+pub mod autocomplete;
+pub mod cache;
+pub mod compare;
+pub mod ctx_util;
+pub mod dates;
+pub mod errors;
+pub mod feed;
+pub mod keys;
 pub mod lbxd_util;
 pub mod paginator;
+pub mod picker;
+pub mod render;
+pub mod rss;
+pub mod store;
 pub mod structs;
 pub mod tmdb_util;
+pub mod unfurl;
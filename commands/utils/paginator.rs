@@ -2,67 +2,182 @@
 
 use poise::serenity_prelude as serenity;
 use serenity::ReactionType::Unicode;
+use std::time::Duration;
 
-#[allow(dead_code)]
-pub async fn start_images<U, E>(
+/// How long the buttons keep flipping pages.
+pub const TIMEOUT: Duration = Duration::from_secs(3600 * 24);
+
+/// A button under a page's navigation, which whoever sent the pages answers.
+#[derive(Clone, Debug, Default)]
+pub struct Action {
+    pub custom_id: String,
+    pub label: String,
+}
+
+/// One page of a paginated embed, every field but the title being optional.
+#[derive(Clone, Debug, Default)]
+pub struct Page {
+    pub title: String,
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub thumbnail: Option<String>,
+    pub image: Option<String>,
+    /// Shown after the page number.
+    pub footer: Option<String>,
+    /// Sent as the message text above the embed, which is where Discord plays video links.
+    pub content: Option<String>,
+    /// At most five, in a row under the navigation buttons.
+    pub actions: Vec<Action>,
+}
+
+/// Spreads `lines` over pages of `per_page` lines, all titled `title`.
+pub fn text_pages(title: &str, lines: &[String], per_page: usize) -> Vec<Page> {
+    lines
+        .chunks(per_page)
+        .map(|chunk| Page {
+            title: title.to_string(),
+            description: Some(chunk.join("\n")),
+            ..Default::default()
+        })
+        .collect()
+}
+
+struct ButtonIds {
+    first: String,
+    prev: String,
+    next: String,
+    last: String,
+}
+
+fn embed<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    page: &Page,
+    current_page: usize,
+    page_count: usize,
+    color: serenity::Colour,
+) -> &'a mut serenity::CreateEmbed {
+    if let Some(url) = &page.url {
+        e.url(url);
+    }
+    if let Some(description) = &page.description {
+        e.description(description);
+    }
+    if let Some(thumbnail) = &page.thumbnail {
+        e.thumbnail(thumbnail);
+    }
+    if let Some(image) = &page.image {
+        e.image(image);
+    }
+    let footer = match &page.footer {
+        Some(footer) => format!("Page {}/{} • {}", current_page + 1, page_count, footer),
+        None => format!("Page {}/{}", current_page + 1, page_count),
+    };
+    e.title(&page.title)
+        .color(color)
+        .footer(|f| f.text(footer))
+}
+
+fn buttons<'a>(
+    c: &'a mut serenity::CreateComponents,
+    ids: &ButtonIds,
+    current_page: usize,
+    page_count: usize,
+    actions: &[Action],
+) -> &'a mut serenity::CreateComponents {
+    if !actions.is_empty() {
+        c.create_action_row(|b| {
+            for action in actions {
+                b.create_button(|b| {
+                    b.custom_id(&action.custom_id)
+                        .style(serenity::ButtonStyle::Secondary)
+                        .label(&action.label)
+                });
+            }
+            b
+        });
+    }
+    // A single page has nothing to flip through.
+    if page_count < 2 {
+        return c;
+    }
+    c.create_action_row(|b| {
+        b.create_button(|b| {
+            b.custom_id(&ids.first)
+                .emoji(Unicode("⏪".to_string()))
+                .disabled(current_page == 0)
+        })
+        .create_button(|b| {
+            b.custom_id(&ids.prev)
+                .emoji(Unicode("◀️".to_string()))
+                .disabled(current_page == 0)
+        })
+        .create_button(|b| {
+            b.custom_id(&ids.next)
+                .emoji(Unicode("▶️".to_string()))
+                .disabled(current_page == page_count - 1)
+        })
+        .create_button(|b| {
+            b.custom_id(&ids.last)
+                .emoji(Unicode("⏩".to_string()))
+                .disabled(current_page == page_count - 1)
+        })
+    })
+}
+
+/// Sends `pages` as one embed with buttons to flip through them, which only `author` can use.
+pub async fn start<U, E>(
     ctx: poise::Context<'_, U, E>,
-    title: &str,
     color: serenity::Colour,
     author: u64,
-    pages: &[&str],
+    pages: &[Page],
 ) -> Result<(), serenity::Error> {
+    if pages.is_empty() {
+        return Ok(());
+    }
     let ctx_id = ctx.id();
-    let prev_button_id = format!("{}prev", ctx.id());
-    let next_button_id = format!("{}next", ctx.id());
-    let first_button_id = format!("{}first", ctx.id());
-    let last_button_id = format!("{}last", ctx.id());
+    let ids = ButtonIds {
+        first: format!("{}first", ctx_id),
+        prev: format!("{}prev", ctx_id),
+        next: format!("{}next", ctx_id),
+        last: format!("{}last", ctx_id),
+    };
+
+    // Once a page has text, flipping to one without has to clear it.
+    let has_content = pages.iter().any(|page| page.content.is_some());
+    let content = |page: &Page| page.content.clone().unwrap_or_default();
 
     let mut current_page = 0;
     ctx.send(|b| {
-        b.embed(|b| {
-            b.title(title)
-                .color(color)
-                .image(pages[current_page])
-                .footer(|f| f.text(format!("Page {}/{}", current_page + 1, pages.len())))
-        })
-        .components(|b| {
-            b.create_action_row(|b| {
-                b.create_button(|b| {
-                    b.custom_id(&first_button_id)
-                        .emoji(Unicode("⏪".to_string()))
-                        .disabled(true)
-                })
-                .create_button(|b| {
-                    b.custom_id(&prev_button_id)
-                        .emoji(Unicode("◀️".to_string()))
-                        .disabled(true)
-                })
-                .create_button(|b| b.custom_id(&next_button_id).emoji(Unicode("▶️".to_string())))
-                .create_button(|b| {
-                    b.custom_id(&last_button_id)
-                        .emoji(Unicode("⏩".to_string()))
-                })
+        if has_content {
+            b.content(content(&pages[current_page]));
+        }
+        b.embed(|e| embed(e, &pages[current_page], current_page, pages.len(), color))
+            .components(|c| {
+                let actions = &pages[current_page].actions;
+                buttons(c, &ids, current_page, pages.len(), actions)
             })
-        })
     })
     .await?;
+    if pages.len() == 1 {
+        return Ok(());
+    }
 
     while let Some(press) = serenity::CollectComponentInteraction::new(ctx)
         .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
-        .timeout(std::time::Duration::from_secs(3600 * 24))
+        .timeout(TIMEOUT)
         .author_id(author)
         .await
     {
-        if press.data.custom_id == next_button_id {
+        if press.data.custom_id == ids.next {
             current_page += 1;
             if current_page >= pages.len() {
                 current_page = 0;
             }
-        } else if press.data.custom_id == prev_button_id {
+        } else if press.data.custom_id == ids.prev {
             current_page = current_page.checked_sub(1).unwrap_or(pages.len() - 1);
-        } else if press.data.custom_id == first_button_id {
+        } else if press.data.custom_id == ids.first {
             current_page = 0;
-        } else if press.data.custom_id == last_button_id {
+        } else if press.data.custom_id == ids.last {
             current_page = pages.len() - 1;
         } else {
             continue;
@@ -72,37 +187,15 @@ pub async fn start_images<U, E>(
             .create_interaction_response(ctx, |b| {
                 b.kind(serenity::InteractionResponseType::UpdateMessage)
                     .interaction_response_data(|b| {
-                        b.embed(|b| {
-                            b.title(title)
-                                .color(color)
-                                .footer(|f| {
-                                    f.text(format!("Page {}/{}", current_page + 1, pages.len()))
-                                })
-                                .image(pages[current_page])
+                        if has_content {
+                            b.content(content(&pages[current_page]));
+                        }
+                        b.embed(|e| {
+                            embed(e, &pages[current_page], current_page, pages.len(), color)
                         })
-                        .components(|b| {
-                            b.create_action_row(|b| {
-                                b.create_button(|b| {
-                                    b.custom_id(&first_button_id)
-                                        .emoji(Unicode("⏪".to_string()))
-                                        .disabled(current_page == 0)
-                                })
-                                .create_button(|b| {
-                                    b.custom_id(&prev_button_id)
-                                        .emoji(Unicode("◀️".to_string()))
-                                        .disabled(current_page == 0)
-                                })
-                                .create_button(|b| {
-                                    b.custom_id(&next_button_id)
-                                        .emoji(Unicode("▶️".to_string()))
-                                        .disabled(current_page == pages.len() - 1)
-                                })
-                                .create_button(|b| {
-                                    b.custom_id(&last_button_id)
-                                        .emoji(Unicode("⏩".to_string()))
-                                        .disabled(current_page == pages.len() - 1)
-                                })
-                            })
+                        .components(|c| {
+                            let actions = &pages[current_page].actions;
+                            buttons(c, &ids, current_page, pages.len(), actions)
                         })
                     })
             })
@@ -111,3 +204,22 @@ pub async fn start_images<U, E>(
 
     Ok(())
 }
+
+/// Pages through `images`, one per page.
+pub async fn start_images<U, E>(
+    ctx: poise::Context<'_, U, E>,
+    title: &str,
+    color: serenity::Colour,
+    author: u64,
+    images: &[&str],
+) -> Result<(), serenity::Error> {
+    let pages = images
+        .iter()
+        .map(|image| Page {
+            title: title.to_string(),
+            image: Some(image.to_string()),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    start(ctx, color, author, &pages).await
+}
@@ -0,0 +1,97 @@
+use crate::commands::utils::keys;
+
+use poise::serenity_prelude as serenity;
+
+/// Discord caps select menus at 25 options, but more than a handful is just noise.
+pub const MAX_CHOICES: usize = 5;
+
+/// How a search result was settled on.
+pub enum Picked<T> {
+    /// The search was unambiguous (or bypassed with a year or URL).
+    One(T),
+    /// The user picked it from the menu.
+    Chosen(T),
+    NotFound,
+    /// The user never picked anything.
+    Cancelled,
+}
+
+pub struct Choice {
+    pub label: String,
+    pub description: String,
+}
+
+/// A search is ambiguous when more than one of the top results has exactly the queried title.
+pub fn is_ambiguous<'a>(query: &str, titles: impl Iterator<Item = &'a str>) -> bool {
+    let query = keys::title(query, None);
+    titles
+        .take(MAX_CHOICES)
+        .filter(|title| keys::title(title, None) == query)
+        .count()
+        > 1
+}
+
+fn clip(text: &str) -> String {
+    // Select menu labels and descriptions are capped at 100 characters.
+    if text.chars().count() > 100 {
+        format!("{}…", text.chars().take(99).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Shows a select menu with `choices` and waits for the author to pick one.
+pub async fn pick<U, E>(
+    ctx: poise::Context<'_, U, E>,
+    prompt: &str,
+    choices: &[Choice],
+) -> Result<Option<usize>, serenity::Error> {
+    let menu_id = format!("{}pick", ctx.id());
+    let reply = ctx
+        .send(|m| {
+            m.content(prompt).components(|c| {
+                c.create_action_row(|ar| {
+                    ar.create_select_menu(|s| {
+                        s.custom_id(&menu_id)
+                            .placeholder("Pick one")
+                            .options(|o| {
+                                for (i, choice) in choices.iter().enumerate().take(MAX_CHOICES) {
+                                    o.create_option(|opt| {
+                                        opt.label(clip(&choice.label)).value(i);
+                                        if !choice.description.is_empty() {
+                                            opt.description(clip(&choice.description));
+                                        }
+                                        opt
+                                    });
+                                }
+                                o
+                            })
+                    })
+                })
+            })
+        })
+        .await?;
+
+    let filter_id = menu_id.clone();
+    let press = serenity::CollectComponentInteraction::new(ctx)
+        .filter(move |press| press.data.custom_id == filter_id)
+        .author_id(ctx.author().id)
+        .timeout(std::time::Duration::from_secs(60))
+        .await;
+    let picked = press.as_ref().and_then(|press| {
+        press
+            .data
+            .values
+            .first()
+            .and_then(|value| value.parse::<usize>().ok())
+    });
+    if let Some(press) = press {
+        press
+            .create_interaction_response(ctx, |r| {
+                r.kind(serenity::InteractionResponseType::DeferredUpdateMessage)
+            })
+            .await?;
+    }
+    reply.delete(ctx).await?;
+    Ok(picked)
+}
@@ -0,0 +1,585 @@
+use crate::commands::utils::{
+    compare::Comparison,
+    dates::Date,
+    structs::{DiaryResult, Film, ListEntry, ListResult, ProfileResult, ReviewResult},
+    tmdb_util::{RegionProviders, TmdbFilm},
+};
+use crate::config;
+
+use poise::serenity_prelude as serenity;
+
+/// For embeds that aren't answering anyone, so there's no member color to use.
+pub const LETTERBOXD_GREEN: serenity::Colour = serenity::Colour::new(0x00e054);
+
+/// How many of the top-billed actors film details list.
+const TOP_BILLED: usize = 5;
+
+pub fn starrize(rating: f32) -> String {
+    if rating == 0.0 {
+        return String::new();
+    }
+
+    let clean_rating = (rating * 2.0).round() / 2.0;
+
+    let rounded = clean_rating.floor() as usize;
+    let mut star_string = "<:lbstar:1061604009783341117>".repeat(rounded);
+
+    if clean_rating.fract() != 0.0 {
+        star_string += "<:lbhstar:1061603475949096991>";
+    }
+
+    star_string
+}
+
+pub fn runtime(minutes: u32) -> String {
+    let hours = minutes / 60;
+    let minutes = minutes % 60;
+    match (hours, minutes) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Shortens big counts the way Letterboxd does, e.g. `1.2k` or `2.35m`.
+pub fn compact_count(number: u64) -> String {
+    let number = number as f64;
+    match number {
+        n if n >= 1_000_000.0 => format!("{:.2}m", number / 1_000_000.0),
+        n if n >= 1_000.0 => format!("{:.1}k", number / 1_000.0),
+        _ => format!("{:.0}", number),
+    }
+}
+
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        format!("{}...", text.chars().take(max_chars).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+/// A diary date in words for the past few days, as a Discord timestamp before that so it
+/// shows in the reader's locale.
+pub fn diary_date(date: Date, today: Date) -> String {
+    date.days_ago(today)
+        .unwrap_or_else(|| format!("<t:{}:D>", date.timestamp()))
+}
+
+/// The date, rating and rewatch/like/review flags of a diary entry.
+pub fn diary_line(diary: &DiaryResult) -> String {
+    let rewatched = if diary.rewatched {
+        "🔄 ".to_string()
+    } else {
+        String::new()
+    };
+    let liked = if diary.liked {
+        "❤️ ".to_string()
+    } else {
+        String::new()
+    };
+    let reviewed = if diary.reviewed {
+        "💬".to_string()
+    } else {
+        String::new()
+    };
+    let rating = if !diary.rating.is_empty() {
+        format!("{} ", diary.rating)
+    } else {
+        String::new()
+    };
+    format!(
+        "{} {}{}{}{}",
+        diary_date(diary.date, Date::today()),
+        rating,
+        rewatched,
+        liked,
+        reviewed
+    )
+}
+
+/// Lists diary entries with their links, one entry per two lines.
+pub fn diary_entries(entries: &[DiaryResult]) -> String {
+    entries
+        .iter()
+        .map(|d| format!("[**{}**]({})\n{}\n", d.title, d.url, diary_line(d)))
+        .collect()
+}
+
+/// How many of `entries` are liked, rewatched and reviewed.
+pub fn diary_counts(entries: &[DiaryResult]) -> String {
+    let count = |f: fn(&DiaryResult) -> bool| entries.iter().filter(|e| f(e)).count();
+    format!(
+        "{} entr{}, {} liked, {} rewatched, {} reviewed",
+        entries.len(),
+        ["ies", "y"][(entries.len() == 1) as usize],
+        count(|e| e.liked),
+        count(|e| e.rewatched),
+        count(|e| e.reviewed)
+    )
+}
+
+/// Lists list entries with their links and a peek at their notes, `base_url` being
+/// Letterboxd's.
+pub fn list_entries(entries: &[ListEntry], base_url: &str) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            let mut line = match entry.rank {
+                Some(rank) => format!("**{}.** ", rank),
+                None => "• ".to_string(),
+            };
+            line.push_str(&format!(
+                "[{}]({}{})\n",
+                entry.display_title(),
+                base_url,
+                entry.path
+            ));
+            if let Some(note) = &entry.note {
+                line.push_str(&format!("> {}\n", truncate(&note.replace('\n', " "), 150)));
+            }
+            line
+        })
+        .collect()
+}
+
+/// Hides `text` behind a Discord spoiler.
+pub fn spoiler(text: &str) -> String {
+    // `||` inside the text would close the spoiler early.
+    format!("||{}||", text.replace("||", "|\u{200b}|"))
+}
+
+pub fn review<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    review: &ReviewResult,
+    color: serenity::Colour,
+) -> &'a mut serenity::CreateEmbed {
+    let mut description = String::new();
+    if !review.rating.is_empty() {
+        description.push_str(&format!("{}\n\n", review.rating));
+    }
+    // Embed descriptions are capped at 4096 characters.
+    let text = truncate(&review.text, 3800);
+    if review.spoilers {
+        description.push_str("*This review may contain spoilers.*\n");
+        description.push_str(&spoiler(&text));
+    } else {
+        description.push_str(&text);
+    }
+    if let Some(poster) = &review.poster {
+        e.thumbnail(poster);
+    }
+    e.title(format!("{}'s review of {}", review.username, review.film))
+        .url(&review.url)
+        .description(description)
+        .footer(|f| {
+            f.text(format!(
+                "❤️ {} • 💬 {}",
+                compact_count(review.likes),
+                compact_count(review.comments)
+            ))
+        })
+        .color(color)
+}
+
+pub fn profile<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    user: &ProfileResult,
+    color: serenity::Colour,
+) -> &'a mut serenity::CreateEmbed {
+    let mut description = String::new();
+    if !user.location.is_empty() || !user.bio.is_empty() {
+        description.push_str(&format!(
+            "{}\n{}\n———————————————\n",
+            user.location, user.bio
+        ));
+    }
+    if !user.favorites.is_empty() {
+        description.push_str(&format!("{}\n", user.favorites));
+    }
+    if !user.avatar.is_empty() {
+        e.thumbnail(&user.avatar);
+    }
+    let followers = user.followers.replace(',', "").parse::<i32>().unwrap_or(0);
+    e.author(|a| {
+        a.icon_url(
+            "https://cdn.discordapp.com/emojis/710193146843365457.webp?size=96&quality=lossless",
+        )
+        .name(&user.username)
+    })
+    .title(&user.name)
+    .description(description)
+    .color(color)
+    .url(&user.url)
+    .footer(|f| {
+        f.text(format!(
+            "{} follower{}, {}",
+            user.followers,
+            ["", "s"][(followers != 1) as usize],
+            user.films_count
+        ))
+    })
+}
+
+/// The first entries of a list, for when there's no paginator to show them all.
+pub fn list<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    list: &ListResult,
+    base_url: &str,
+    color: serenity::Colour,
+) -> &'a mut serenity::CreateEmbed {
+    let mut description = String::new();
+    if let Some(list_description) = &list.description {
+        description.push_str(&format!("{}\n\n", truncate(list_description, 300)));
+    }
+    let shown = list.entries.len().min(config::LIST_ENTRIES_PER_PAGE);
+    description.push_str(&list_entries(&list.entries[..shown], base_url));
+    if let Some(poster) = list.entries.iter().find_map(|entry| entry.poster.as_ref()) {
+        e.thumbnail(poster);
+    }
+    e.title(&list.title)
+        .url(&list.url)
+        .description(description)
+        .footer(|f| {
+            f.text(format!(
+                "List by {} • {} film{}",
+                list.author,
+                list.entries.len(),
+                ["", "s"][(list.entries.len() != 1) as usize]
+            ))
+        })
+        .color(color)
+}
+
+/// How alike `first` and `second` rate films, `base_url` being Letterboxd's.
+pub fn comparison<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    first: &str,
+    second: &str,
+    comparison: &Comparison,
+    base_url: &str,
+    color: serenity::Colour,
+) -> &'a mut serenity::CreateEmbed {
+    let mut description = format!(
+        "**{}** film{} rated by both\n",
+        comparison.overlap,
+        ["", "s"][(comparison.overlap != 1) as usize]
+    );
+    if let Some(correlation) = comparison.correlation {
+        description.push_str(&format!("Rating correlation: **{:.2}**\n", correlation));
+    }
+    if let Some(mean_difference) = comparison.mean_difference {
+        description.push_str(&format!(
+            "Ratings differ by **{:.2}** stars on average\n",
+            mean_difference
+        ));
+    }
+    if !comparison.disagreements.is_empty() {
+        description.push_str("\n**Biggest disagreements**\n");
+        for film in &comparison.disagreements {
+            description.push_str(&format!(
+                "[{}]({}{})\n{}: {} • {}: {}\n",
+                film.title,
+                base_url,
+                film.path,
+                first,
+                starrize(film.first),
+                second,
+                starrize(film.second)
+            ));
+        }
+    }
+    if !comparison.favorites.is_empty() {
+        description.push_str("\n**Both gave five stars**\n");
+        for film in &comparison.favorites {
+            description.push_str(&format!("• [{}]({}{})\n", film.title, base_url, film.path));
+        }
+    }
+    e.title(format!("{} vs {}", first, second))
+        .description(description)
+        .color(color)
+}
+
+pub fn film<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    film: &Film,
+    color: serenity::Colour,
+) -> &'a mut serenity::CreateEmbed {
+    let mut description = String::new();
+    if let Some(tagline) = &film.tagline {
+        description.push_str(&format!("**{}**\n", tagline));
+    }
+    if let Some(synopsis) = &film.synopsis {
+        description.push_str(&truncate(synopsis, 100));
+    }
+    description.push_str("\n\n");
+    if let Some(rating) = film.average_rating {
+        description.push_str(&format!(
+            "{} {}{}\n",
+            starrize(rating),
+            rating,
+            ["", ".0"][(rating.fract() == 0.0) as usize]
+        ));
+    }
+    if !film.directors.is_empty() {
+        let directors = film
+            .directors
+            .iter()
+            .map(|d| match &d.url {
+                Some(url) => format!("[{}]({})", d.name, url),
+                None => d.name.clone(),
+            })
+            .collect::<Vec<_>>();
+        description.push_str(&format!(
+            "Director{}: {}\n",
+            ["", "s"][(directors.len() > 1) as usize],
+            directors.join(", ")
+        ));
+    }
+    let origin = [film.countries.join(", "), film.genres.join(", ")]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+    if !origin.is_empty() {
+        description.push_str(&format!("{}\n", origin.join(" | ")));
+    }
+    if let Some(minutes) = film.runtime {
+        description.push_str(&format!("{}\n", runtime(minutes)));
+    }
+    description.push_str(&format!(
+        "\u{1f440} {} | ❤️ {} | \u{1f4ac} {}",
+        compact_count(film.stats.watched),
+        compact_count(film.stats.likes),
+        compact_count(film.stats.reviews)
+    ));
+    if let Some(poster) = &film.poster {
+        e.thumbnail(poster);
+    }
+    e.title(film.display_title())
+        .description(description)
+        .url(&film.url)
+        .color(color)
+}
+
+/// Names as `Name`, `Name and Other` or `Name, Other and Another`.
+fn names(names: &[&str]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.to_string(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/// A film's TMDB details, with its certification in `region`, e.g. `US`.
+pub fn tmdb_film<'a>(
+    e: &'a mut serenity::CreateEmbed,
+    film: &TmdbFilm,
+    region: &str,
+    color: serenity::Colour,
+) -> &'a mut serenity::CreateEmbed {
+    let mut description = String::new();
+    if film.original_title != film.title {
+        description.push_str(&format!("*{}*\n", film.original_title));
+    }
+    if let Some(tagline) = film.tagline.as_ref().filter(|t| !t.is_empty()) {
+        description.push_str(&format!("**{}**\n", tagline));
+    }
+    if let Some(overview) = film.overview.as_ref().filter(|o| !o.is_empty()) {
+        description.push_str(&truncate(overview, 300));
+    }
+    description.push_str("\n\n");
+    let facts = [
+        film.certification(region).map(|c| c.to_string()),
+        film.runtime.filter(|minutes| *minutes > 0).map(runtime),
+        Some(film.language()),
+    ];
+    let facts = facts.into_iter().flatten().collect::<Vec<_>>();
+    description.push_str(&format!("{}\n", facts.join(" | ")));
+    if !film.genres.is_empty() {
+        let genres = film
+            .genres
+            .iter()
+            .map(|g| g.name.as_str())
+            .collect::<Vec<_>>();
+        description.push_str(&format!("{}\n", genres.join(", ")));
+    }
+    for (role, people) in [("Director", film.directors()), ("Writer", film.writers())] {
+        if !people.is_empty() {
+            description.push_str(&format!(
+                "{}{}: {}\n",
+                role,
+                ["", "s"][(people.len() > 1) as usize],
+                names(&people)
+            ));
+        }
+    }
+    if !film.credits.cast.is_empty() {
+        let cast = film
+            .credits
+            .cast
+            .iter()
+            .take(TOP_BILLED)
+            .map(|actor| match actor.character.as_deref() {
+                Some(character) if !character.is_empty() => {
+                    format!("{} *({})*", actor.name, character)
+                }
+                _ => actor.name.clone(),
+            })
+            .collect::<Vec<_>>();
+        description.push_str(&format!("Starring: {}\n", cast.join(", ")));
+    }
+    let money = [("Budget", film.budget), ("Revenue", film.revenue)]
+        .into_iter()
+        .filter(|(_, dollars)| *dollars > 0)
+        .map(|(label, dollars)| format!("{}: ${}", label, compact_count(dollars)))
+        .collect::<Vec<_>>();
+    if !money.is_empty() {
+        description.push_str(&format!("{}\n", money.join(" | ")));
+    }
+    let mut links = vec![format!("[TMDB]({})", film.url())];
+    if let Some(imdb) = film.imdb_url() {
+        links.push(format!("[IMDb]({})", imdb));
+    }
+    links.push(format!("[Letterboxd]({})", film.letterboxd_url()));
+    description.push_str(&format!("\n{}", links.join(" | ")));
+    if let Some(poster) = film.poster() {
+        e.thumbnail(poster);
+    }
+    let keywords = film
+        .keywords
+        .keywords
+        .iter()
+        .map(|k| k.name.as_str())
+        .collect::<Vec<_>>();
+    if !keywords.is_empty() {
+        e.footer(|f| f.text(truncate(&keywords.join(", "), 200)));
+    }
+    e.title(film.display_title())
+        .description(description)
+        .url(film.url())
+        .color(color)
+}
+
+/// Where `title` can be watched in `region`: a header embed linking to TMDB's watch page, then
+/// one embed per kind of offer with the logo of its top provider.
+pub fn watch_offers(
+    title: &str,
+    region: &str,
+    providers: &RegionProviders,
+    color: serenity::Colour,
+) -> Vec<serenity::CreateEmbed> {
+    let mut header = serenity::CreateEmbed::default();
+    header
+        .title(format!("Where to watch {} in {}", title, region))
+        .url(&providers.link)
+        .description(format!("[All offers on JustWatch]({})", providers.link))
+        .color(color);
+    let mut embeds = vec![header];
+    for (kind, offers) in providers.offers() {
+        let mut e = serenity::CreateEmbed::default();
+        if let Some(logo) = offers[0].logo() {
+            e.thumbnail(logo);
+        }
+        let names = offers
+            .iter()
+            .map(|provider| provider.provider_name.as_str())
+            .collect::<Vec<_>>();
+        e.title(kind)
+            .description(truncate(&names.join("\n"), 1000))
+            .color(color);
+        embeds.push(e);
+    }
+    embeds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_names() {
+        assert_eq!(names(&[]), "");
+        assert_eq!(names(&["Joel Coen"]), "Joel Coen");
+        assert_eq!(
+            names(&["Joel Coen", "Ethan Coen"]),
+            "Joel Coen and Ethan Coen"
+        );
+        assert_eq!(names(&["A", "B", "C"]), "A, B and C");
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(compact_count(999), "999");
+        assert_eq!(compact_count(1_234), "1.2k");
+        assert_eq!(compact_count(654_321), "654.3k");
+        assert_eq!(compact_count(2_345_678), "2.35m");
+    }
+
+    #[test]
+    fn diary_summaries() {
+        let entries = vec![
+            DiaryResult {
+                liked: true,
+                rewatched: true,
+                ..Default::default()
+            },
+            DiaryResult {
+                liked: true,
+                reviewed: true,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(diary_counts(&entries), "2 entries, 2 liked, 1 rewatched, 1 reviewed");
+        assert_eq!(diary_counts(&entries[..1]), "1 entry, 1 liked, 1 rewatched, 0 reviewed");
+    }
+
+    #[test]
+    fn diary_dates() {
+        let today = Date::new(2024, 3, 1).unwrap();
+        assert_eq!(
+            diary_date(Date::new(2024, 2, 29).unwrap(), today),
+            "Yesterday"
+        );
+        assert_eq!(
+            diary_date(Date::new(2023, 3, 14).unwrap(), today),
+            "<t:1678795200:D>"
+        );
+    }
+
+    #[test]
+    fn list_lines() {
+        let entries = vec![
+            ListEntry {
+                rank: Some(1),
+                title: "Alien".to_string(),
+                year: Some(1979),
+                path: "/film/alien/".to_string(),
+                note: Some("Perfect.\nStill.".to_string()),
+                ..Default::default()
+            },
+            ListEntry {
+                title: "Heat".to_string(),
+                path: "/film/heat-1995/".to_string(),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            list_entries(&entries, "https://letterboxd.com"),
+            "**1.** [Alien (1979)](https://letterboxd.com/film/alien/)\n> Perfect. Still.\n\
+             • [Heat](https://letterboxd.com/film/heat-1995/)\n"
+        );
+    }
+
+    #[test]
+    fn spoilers() {
+        assert_eq!(spoiler("Ripley lives"), "||Ripley lives||");
+        assert_eq!(spoiler("a || b"), "||a |\u{200b}| b||");
+    }
+
+    #[test]
+    fn runtimes() {
+        assert_eq!(runtime(45), "45m");
+        assert_eq!(runtime(120), "2h");
+        assert_eq!(runtime(136), "2h 16m");
+    }
+}
@@ -0,0 +1,162 @@
+//! Parses the `/<username>/rss/` feed, a sturdier source for recent diary entries than the
+//! diary page's markup.
+
+use crate::commands::utils::{
+    dates::Date,
+    errors::ScrapeError,
+    lbxd_util::{film_path_from_url, format_bio},
+    render::starrize,
+    structs::DiaryResult,
+};
+
+use roxmltree::{Document, Node};
+use scraper::{Html, Selector};
+
+const LETTERBOXD_NS: &str = "https://letterboxd.com";
+
+/// Paragraphs Letterboxd adds to every entry's description, which aren't part of the review.
+fn is_boilerplate(paragraph: &str) -> bool {
+    paragraph.starts_with("Watched on ")
+        || paragraph.starts_with("Rewatched on ")
+        || paragraph.contains("This review may contain spoilers.")
+}
+
+fn child_text<'a>(item: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    item.children()
+        .find(|n| n.has_tag_name(name))
+        .and_then(|n| n.text())
+        .map(str::trim)
+}
+
+fn letterboxd_text<'a>(item: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    item.children()
+        .find(|n| n.has_tag_name((LETTERBOXD_NS, name)))
+        .and_then(|n| n.text())
+        .map(str::trim)
+}
+
+/// Gets the poster and the review, if any, out of an item's HTML description.
+fn parse_description(description: &str) -> (Option<String>, Option<String>) {
+    let fragment = Html::parse_fragment(description);
+    let paragraph_selector = Selector::parse("p").unwrap();
+    let image_selector = Selector::parse("img").unwrap();
+    let poster = fragment
+        .select(&image_selector)
+        .next()
+        .and_then(|img| img.value().attr("src"))
+        .map(str::to_string);
+    let review = fragment
+        .select(&paragraph_selector)
+        .filter(|p| p.select(&image_selector).next().is_none())
+        .map(|p| format_bio(&p.inner_html()))
+        .filter(|p| !p.is_empty() && !is_boilerplate(p))
+        .collect::<Vec<_>>()
+        .join("\n");
+    (poster, (!review.is_empty()).then_some(review))
+}
+
+/// Parses a diary feed into `(display name, entries)`, newest first.
+///
+/// The feed also has lists and other activity, only diary entries are kept.
+pub fn parse_diary_feed(
+    xml: &str,
+    base_url: &str,
+) -> Result<(String, Vec<DiaryResult>), ScrapeError> {
+    let document = Document::parse(xml)?;
+    let channel = document
+        .descendants()
+        .find(|n| n.has_tag_name("channel"))
+        .ok_or_else(|| ScrapeError::layout("channel"))?;
+    let display_name = child_text(channel, "title")
+        .ok_or_else(|| ScrapeError::layout("channel > title"))?
+        .trim_start_matches("Letterboxd - ")
+        .to_string();
+
+    let mut entries = vec![];
+    for item in channel.children().filter(|n| n.has_tag_name("item")) {
+        let Some(watched_date) = letterboxd_text(item, "watchedDate") else {
+            continue;
+        };
+        let title = letterboxd_text(item, "filmTitle")
+            .ok_or_else(|| ScrapeError::layout("letterboxd:filmTitle"))?;
+        let title = match letterboxd_text(item, "filmYear") {
+            Some(year) => format!("{} ({})", title, year),
+            None => title.to_string(),
+        };
+        let link = child_text(item, "link").ok_or_else(|| ScrapeError::layout("item > link"))?;
+        // The link points at the member's entry, use the film's page like the diary does.
+        let url = match film_path_from_url(link) {
+            Some(path) => format!("{}{}", base_url, path.trim_end_matches('/')),
+            None => link.to_string(),
+        };
+        let date = Date::parse_iso(watched_date)
+            .ok_or_else(|| ScrapeError::Parse(format!("watched date `{}`", watched_date)))?;
+        let stars = match letterboxd_text(item, "memberRating") {
+            Some(rating) => Some(rating.parse::<f32>()?),
+            None => None,
+        };
+        let rating = stars.map(starrize).unwrap_or_default();
+        let description = child_text(item, "description").unwrap_or_default();
+        let (poster, review) = parse_description(description);
+        entries.push(DiaryResult {
+            title,
+            rating,
+            rewatched: letterboxd_text(item, "rewatch") == Some("Yes"),
+            liked: letterboxd_text(item, "memberLike") == Some("Yes"),
+            reviewed: review.is_some(),
+            date,
+            url,
+            review,
+            poster,
+            stars,
+        });
+    }
+    Ok((display_name, entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_URL: &str = "https://letterboxd.com";
+
+    #[test]
+    fn diary_feed() {
+        let feed = include_str!("../../fixtures/letterboxd/diary.rss");
+        let (display_name, entries) = parse_diary_feed(feed, BASE_URL).unwrap();
+        assert_eq!(display_name, "Jane Doe");
+        assert_eq!(entries.len(), 2);
+
+        let alien = &entries[0];
+        assert_eq!(alien.title, "Alien (1979)");
+        assert_eq!(alien.url, "https://letterboxd.com/film/alien");
+        assert_eq!(alien.date, Date::new(2023, 3, 14).unwrap());
+        assert_eq!(alien.rating, starrize(4.5));
+        assert_eq!(alien.stars, Some(4.5));
+        assert!(alien.liked && alien.rewatched && alien.reviewed);
+        assert_eq!(
+            alien.review.as_deref(),
+            Some("Perfect. **Still** the best.\nIn space no one can hear you scream.")
+        );
+        assert!(alien
+            .poster
+            .as_deref()
+            .unwrap()
+            .ends_with("51714-alien-0-600-0-900-crop.jpg"));
+
+        let heat = &entries[1];
+        assert_eq!(heat.title, "Heat (1995)");
+        assert_eq!(heat.date, Date::new(2022, 1, 2).unwrap());
+        assert_eq!(heat.rating, "");
+        assert!(!heat.liked && !heat.rewatched && !heat.reviewed);
+        assert!(heat.review.is_none());
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(matches!(
+            parse_diary_feed("<html>", BASE_URL),
+            Err(ScrapeError::Parse(_))
+        ));
+    }
+}
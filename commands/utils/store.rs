@@ -0,0 +1,125 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::{collections::HashMap, fs, path::{Path, PathBuf}, sync::Mutex};
+
+/// Writes `value` as JSON to `path`, going through a temporary file so a crash mid-write
+/// can't leave a truncated file behind.
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string(value).map_err(|why| why.to_string())?;
+    write_atomic(path, &json)
+}
+
+/// Writes `json` to `path` the way [`save_json`] does, for callers that serialized it already.
+pub fn write_atomic(path: &Path, json: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|why| why.to_string())?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json).map_err(|why| why.to_string())?;
+    fs::rename(&tmp, path).map_err(|why| why.to_string())
+}
+
+/// A persisted map for data that must never expire or be evicted, unlike a [`Cache`].
+///
+/// Entries are saved to `<dir>/<name>.json` on every change.
+///
+/// [`Cache`]: crate::commands::utils::cache::Cache
+#[derive(Debug)]
+pub struct Store<V> {
+    name: &'static str,
+    path: Option<PathBuf>,
+    entries: Mutex<HashMap<String, V>>,
+}
+
+impl<V: Clone + Serialize + DeserializeOwned> Store<V> {
+    /// Opens the store saved in `dir`, `None` keeping it in memory only.
+    pub fn open(name: &'static str, dir: Option<&str>) -> Self {
+        let path = dir.map(|dir| PathBuf::from(dir).join(format!("{}.json", name)));
+        let mut entries = HashMap::new();
+        if let Some(saved) = path.as_ref().and_then(|path| fs::read_to_string(path).ok()) {
+            match serde_json::from_str(&saved) {
+                Ok(saved) => entries = saved,
+                Err(why) => {
+                    // Unlike a cache this can't be rebuilt, so keep the file around for a human.
+                    eprintln!("Couldn't load the {} store, starting empty: {}", name, why);
+                    let path = path.as_ref().unwrap();
+                    let _ = fs::copy(path, path.with_extension("json.bad"));
+                }
+            }
+        }
+        Self {
+            name,
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<V> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    pub fn insert(&self, key: impl Into<String>, value: V) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(key.into(), value);
+        self.save(&entries);
+    }
+
+    pub fn remove(&self, key: &str) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        let removed = entries.remove(key);
+        if removed.is_some() {
+            self.save(&entries);
+        }
+        removed
+    }
+
+    /// Changes the entry under `key` in place, starting from the default value if missing.
+    pub fn update<R>(&self, key: &str, f: impl FnOnce(&mut V) -> R) -> R
+    where
+        V: Default,
+    {
+        let mut entries = self.entries.lock().unwrap();
+        let result = f(entries.entry(key.to_string()).or_default());
+        self.save(&entries);
+        result
+    }
+
+    /// A snapshot of every entry.
+    pub fn entries(&self) -> Vec<(String, V)> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    fn save(&self, entries: &HashMap<String, V>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(why) = save_json(path, entries) {
+            eprintln!("Couldn't save the {} store: {}", self.name, why);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn survives_reopening() {
+        let dir = std::env::temp_dir().join(format!("filmbro-store-{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let store = Store::open("links", Some(dir));
+        store.insert("1", "janedoe".to_string());
+        store.insert("2", "newbie".to_string());
+        assert_eq!(store.remove("2"), Some("newbie".to_string()));
+
+        let reopened = Store::<String>::open("links", Some(dir));
+        assert_eq!(reopened.get("1"), Some("janedoe".to_string()));
+        assert_eq!(reopened.get("2"), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,37 +1,184 @@
-use std::{collections::HashMap, sync::RwLock};
+use crate::commands::utils::{
+    autocomplete::Debouncer,
+    cache::{Cache, Flush},
+    dates::Date,
+    errors::ScrapeError,
+    keys,
+    lbxd_util::LetterboxdClient,
+    store::Store,
+    tmdb_util::{RegionProviders, TmdbCandidate, TmdbClient, TmdbFilm, TmdbPerson},
+    unfurl::Cooldown,
+};
+use crate::config;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-type DiaryCache = RwLock<HashMap<String, (String, String, Vec<DiaryResult>)>>;
-#[derive(Clone, Debug, Default)]
+type Error = Box<dyn std::error::Error + Send + Sync>;
+type DiaryCache = Cache<(Option<String>, String, Vec<DiaryResult>)>;
+type ImageCache = Cache<(String, Vec<String>)>;
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct DiaryResult {
-    pub found: bool,
     pub title: String,
     pub rating: String,
     pub rewatched: bool,
     pub liked: bool,
     pub reviewed: bool,
-    pub date: String,
+    /// When the film was watched.
+    pub date: Date,
     pub url: String,
+    #[serde(default)]
+    pub review: Option<String>,
+    /// Only known when the entry comes from the RSS feed.
+    #[serde(default)]
+    pub poster: Option<String>,
+    /// The rating out of 5, `rating` being its stars.
+    #[serde(default)]
+    pub stars: Option<f32>,
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct FilmResult {
-    pub found: bool,
-    pub title: String,
-    pub tagline: String,
-    pub synopsis: String,
+impl DiaryResult {
+    /// Identifies an entry across polls of the diary feed.
+    pub fn feed_key(&self) -> String {
+        format!("{}|{}", self.url, self.date)
+    }
+}
+
+/// A member's latest entry of a film, with its review if they wrote one.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ReviewResult {
+    /// The film title with its year, e.g. `Alien (1979)`.
+    pub film: String,
+    pub username: String,
     pub rating: String,
-    pub genre: String,
-    pub duration: String,
-    pub directors: String,
-    pub countries: String,
-    pub poster: String,
-    pub info: HashMap<String, String>,
+    pub stars: Option<f32>,
+    /// The review as Markdown, empty when the film was only logged.
+    pub text: String,
+    pub spoilers: bool,
+    pub likes: u64,
+    pub comments: u64,
+    pub poster: Option<String>,
     pub url: String,
 }
 
-#[derive(Clone, Debug, Default)]
+/// One page of a `/<username>/films/diary/` listing.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DiaryPage {
+    pub avatar: String,
+    pub display_name: String,
+    pub entries: Vec<DiaryResult>,
+    pub has_next: bool,
+}
+
+/// A film in Letterboxd's search results.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FilmCandidate {
+    pub title: String,
+    pub year: Option<i32>,
+    pub directors: Vec<String>,
+    /// Letterboxd path of the film, e.g. `/film/alien/`.
+    pub path: String,
+}
+
+impl FilmCandidate {
+    pub fn display_title(&self) -> String {
+        match self.year {
+            Some(year) => format!("{} ({})", self.title, year),
+            None => self.title.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Person {
+    pub name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct FilmStats {
+    pub watched: u64,
+    pub likes: u64,
+    pub reviews: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Film {
+    pub slug: String,
+    pub title: String,
+    pub year: Option<i32>,
+    pub tagline: Option<String>,
+    pub synopsis: Option<String>,
+    /// Average rating out of 5, `None` until enough members rated it.
+    pub average_rating: Option<f32>,
+    /// Runtime in minutes.
+    pub runtime: Option<u32>,
+    pub directors: Vec<Person>,
+    pub genres: Vec<String>,
+    pub countries: Vec<String>,
+    pub poster: Option<String>,
+    pub stats: FilmStats,
+    pub url: String,
+}
+
+impl Film {
+    /// The title with the release year, e.g. `Alien (1979)`.
+    pub fn display_title(&self) -> String {
+        match self.year {
+            Some(year) => format!("{} ({})", self.title, year),
+            None => self.title.clone(),
+        }
+    }
+}
+
+/// A film in a member's list.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ListEntry {
+    /// The position in a ranked list.
+    pub rank: Option<u32>,
+    pub title: String,
+    pub year: Option<i32>,
+    /// Letterboxd path of the film, e.g. `/film/alien/`.
+    pub path: String,
+    pub poster: Option<String>,
+    /// What the author wrote about the film, as Markdown.
+    pub note: Option<String>,
+}
+
+impl ListEntry {
+    pub fn display_title(&self) -> String {
+        match self.year {
+            Some(year) => format!("{} ({})", self.title, year),
+            None => self.title.clone(),
+        }
+    }
+}
+
+/// A member's list, with the entries of every page fetched.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ListResult {
+    pub title: String,
+    pub description: Option<String>,
+    /// The author's display name.
+    pub author: String,
+    pub ranked: bool,
+    pub entries: Vec<ListEntry>,
+    pub url: String,
+    /// Whether the list has more pages than were fetched.
+    pub truncated: bool,
+}
+
+/// A film a member rated.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RatedFilm {
+    pub title: String,
+    /// Letterboxd path of the film, e.g. `/film/alien/`.
+    pub path: String,
+    /// Out of 5.
+    pub stars: f32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ProfileResult {
-    pub found: bool,
     pub avatar: String,
     pub username: String,
     pub name: String,
@@ -44,13 +191,241 @@ pub struct ProfileResult {
     pub url: String,
 }
 
-#[derive(Debug, Default)]
+/// The Letterboxd account a Discord user linked.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LinkedAccount {
+    pub username: String,
+    /// Whether the user proved they own the account by putting `token` in their bio.
+    pub verified: bool,
+    /// Handed out by `/letterboxd link`, a new one each time.
+    pub token: String,
+}
+
+/// A member whose new diary entries get posted into a channel.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Follow {
+    pub username: String,
+    pub channel_id: u64,
+    /// Feed keys of the entries already posted, newest last.
+    #[serde(default)]
+    pub seen: Vec<String>,
+}
+
+/// Per-guild configuration.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GuildSettings {
+    #[serde(default)]
+    pub follows: Vec<Follow>,
+    /// Whether Letterboxd links posted in chat get answered with embeds.
+    #[serde(default)]
+    pub unfurl: bool,
+    /// The country code films are looked up for, [`config::DEFAULT_REGION`] when unset.
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+#[derive(Debug)]
 pub struct Data {
+    pub lbxd: LetterboxdClient,
+    pub tmdb: TmdbClient,
     pub diary_cache: DiaryCache,
-    pub film_cache: RwLock<HashMap<String, FilmResult>>,
-    pub poster_cache: RwLock<HashMap<String, (String, Vec<String>)>>,
-    pub backdrop_cache: RwLock<HashMap<String, (String, Vec<String>)>>,
-    pub profile_cache: RwLock<HashMap<String, ProfileResult>>,
+    pub diary_page_cache: Cache<DiaryPage>,
+    pub film_cache: Cache<Film>,
+    /// TMDB details of films, by TMDB id with title lookups aliased to them.
+    pub details_cache: Cache<TmdbFilm>,
+    /// Where films can be watched by country code, by TMDB id.
+    pub watch_cache: Cache<HashMap<String, RegionProviders>>,
+    /// TMDB people with their filmographies, by TMDB id with name lookups aliased to them.
+    pub person_cache: Cache<TmdbPerson>,
+    pub list_cache: Cache<ListResult>,
+    pub watchlist_cache: Cache<ListResult>,
+    /// Every film a member rated, by username.
+    pub ratings_cache: Cache<Vec<RatedFilm>>,
+    pub poster_cache: ImageCache,
+    pub backdrop_cache: ImageCache,
+    pub profile_cache: Cache<ProfileResult>,
+    pub review_cache: Cache<ReviewResult>,
+    /// Film title suggestions by partial query, kept in memory only.
+    pub suggestion_cache: Cache<Vec<TmdbCandidate>>,
+    /// Usernames that were looked up, suggested when typing one.
+    pub usernames: Cache<String>,
+    pub debouncer: Debouncer,
+    /// Linked accounts by Discord user id.
+    pub links: Store<LinkedAccount>,
+    /// Guild settings by guild id, shared with the diary feed watcher.
+    pub guilds: Arc<Store<GuildSettings>>,
+    pub unfurl_cooldown: Cooldown,
+}
+
+fn cache<V>(name: &'static str, ttl: Duration) -> Cache<V>
+where
+    V: Clone + Serialize + DeserializeOwned,
+{
+    Cache::new(name, ttl, config::CACHE_CAPACITY).persist_to(config::CACHE_DIR)
+}
+
+impl Default for Data {
+    fn default() -> Self {
+        Self {
+            lbxd: LetterboxdClient::default(),
+            tmdb: TmdbClient::default(),
+            diary_cache: cache("diary", config::DIARY_CACHE_TTL),
+            diary_page_cache: cache("diary_page", config::DIARY_CACHE_TTL),
+            film_cache: cache("film", config::FILM_CACHE_TTL),
+            details_cache: cache("details", config::FILM_CACHE_TTL),
+            watch_cache: cache("watch", config::WATCH_CACHE_TTL),
+            person_cache: cache("person", config::PERSON_CACHE_TTL),
+            list_cache: cache("list", config::LIST_CACHE_TTL),
+            watchlist_cache: cache("watchlist", config::WATCHLIST_CACHE_TTL),
+            ratings_cache: cache("ratings", config::RATINGS_CACHE_TTL),
+            poster_cache: cache("poster", config::IMAGE_CACHE_TTL),
+            backdrop_cache: cache("backdrop", config::IMAGE_CACHE_TTL),
+            profile_cache: cache("profile", config::PROFILE_CACHE_TTL),
+            review_cache: cache("review", config::REVIEW_CACHE_TTL),
+            suggestion_cache: Cache::new(
+                "suggestion",
+                config::SUGGESTION_CACHE_TTL,
+                config::CACHE_CAPACITY,
+            ),
+            usernames: cache("usernames", config::USERNAME_TTL),
+            debouncer: Debouncer::default(),
+            links: Store::open("links", config::DATA_DIR),
+            guilds: Arc::new(Store::open("guilds", config::DATA_DIR)),
+            unfurl_cooldown: Cooldown::default(),
+        }
+    }
+}
+
+impl Data {
+    /// What writes each persisted cache to disk.
+    pub fn flushers(&self) -> Vec<Arc<dyn Flush>> {
+        vec![
+            self.diary_cache.flusher(),
+            self.diary_page_cache.flusher(),
+            self.film_cache.flusher(),
+            self.details_cache.flusher(),
+            self.watch_cache.flusher(),
+            self.person_cache.flusher(),
+            self.list_cache.flusher(),
+            self.watchlist_cache.flusher(),
+            self.ratings_cache.flusher(),
+            self.poster_cache.flusher(),
+            self.backdrop_cache.flusher(),
+            self.profile_cache.flusher(),
+            self.review_cache.flusher(),
+            self.usernames.flusher(),
+        ]
+    }
+
+    /// Gets the film at `path`, e.g. `/film/alien/`, going through the cache.
+    pub async fn film_at(&self, path: &str) -> Result<Film, ScrapeError> {
+        let slug = path.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+        let canonical = keys::film_slug(slug);
+        if let Some(film) = self.film_cache.get(&canonical) {
+            return Ok(film);
+        }
+        let film = self.lbxd.get_film_at(path).await?;
+        self.film_cache.insert(&canonical, film.clone());
+        Ok(film)
+    }
+
+    /// Gets `username`'s latest entry of the film at `film_path`, going through the cache.
+    pub async fn review(
+        &self,
+        username: &str,
+        film_path: &str,
+    ) -> Result<ReviewResult, ScrapeError> {
+        let key = keys::review(username, film_path);
+        if let Some(review) = self.review_cache.get(&key) {
+            return Ok(review);
+        }
+        let review = self.lbxd.get_review(username, film_path).await?;
+        self.review_cache.insert(&key, review.clone());
+        Ok(review)
+    }
+
+    /// Gets `username`'s list `slug`, going through the cache.
+    pub async fn list(&self, username: &str, slug: &str) -> Result<ListResult, ScrapeError> {
+        let username = keys::username(username);
+        let key = keys::list(&username, slug);
+        if let Some(list) = self.list_cache.get(&key) {
+            return Ok(list);
+        }
+        let list = self.lbxd.get_list(&username, slug).await?;
+        self.list_cache.insert(&key, list.clone());
+        self.usernames.insert(&username, username.clone());
+        Ok(list)
+    }
+
+    /// Gets the TMDB details of the film `id`, going through the cache.
+    pub async fn details(&self, id: u64) -> Result<TmdbFilm, Error> {
+        let key = keys::tmdb_id(id);
+        if let Some(film) = self.details_cache.get(&key) {
+            return Ok(film);
+        }
+        let film = self.tmdb.details(id).await?;
+        self.details_cache.insert(&key, film.clone());
+        Ok(film)
+    }
+
+    /// Gets the TMDB person `id` with their filmography, going through the cache.
+    pub async fn person(&self, id: u64) -> Result<TmdbPerson, Error> {
+        let key = keys::tmdb_person(id);
+        if let Some(person) = self.person_cache.get(&key) {
+            return Ok(person);
+        }
+        let person = self.tmdb.person(id).await?;
+        self.person_cache.insert(&key, person.clone());
+        Ok(person)
+    }
+
+    /// The country code films are looked up for in `guild_id`, the default one outside guilds.
+    pub fn region(&self, guild_id: Option<u64>) -> String {
+        guild_id
+            .and_then(|guild_id| self.guilds.get(&guild_id.to_string()))
+            .and_then(|settings| settings.region)
+            .unwrap_or_else(|| config::DEFAULT_REGION.to_string())
+    }
+
+    /// Gets `username`'s recent diary entries as `(avatar, display name, entries)`, going
+    /// through the cache. The avatar comes from the profile when the diary has none.
+    pub async fn diary(
+        &self,
+        username: &str,
+    ) -> Result<(Option<String>, String, Vec<DiaryResult>), ScrapeError> {
+        let key = keys::username(username);
+        if let Some(diary) = self.diary_cache.get(&key) {
+            return Ok(diary);
+        }
+        let (avatar, display_name, entries) = self.lbxd.get_diary(&key).await?;
+        let avatar = match avatar {
+            Some(avatar) => Some(avatar),
+            None => self
+                .profile(&key)
+                .await
+                .ok()
+                .map(|user| user.avatar)
+                .filter(|avatar| !avatar.is_empty()),
+        };
+        let diary = (avatar, display_name, entries);
+        self.diary_cache.insert(&key, diary.clone());
+        self.usernames.insert(&key, key.clone());
+        Ok(diary)
+    }
+
+    /// Gets `username`'s profile, going through the cache.
+    pub async fn profile(&self, username: &str) -> Result<ProfileResult, ScrapeError> {
+        let key = keys::username(username);
+        if let Some(profile) = self.profile_cache.get(&key) {
+            return Ok(profile);
+        }
+        let profile = self.lbxd.get_profile(&key).await?;
+        let canonical = keys::username(&profile.username);
+        self.profile_cache.insert(&canonical, profile.clone());
+        self.usernames.insert(&canonical, canonical.clone());
+        self.profile_cache.alias(key, canonical);
+        Ok(profile)
+    }
 }
 
 #[allow(dead_code)]
@@ -1,53 +1,963 @@
 #![allow(dead_code)]
 
 use crate::config;
-use poise::serenity_prelude::json;
-
-pub fn get_images(
-    film: String,
-    year: i32,
-    choice: &str,
-) -> Result<(String, Vec<String>), Box<dyn std::error::Error>> {
-    let year = if year != 0 {
-        format!("&year={}", year)
-    } else {
-        String::new()
-    };
-    let data: json::Value = reqwest::blocking::get(format!("https://api.themoviedb.org/3/search/movie?api_key={}&language=en-US&query={}&page=1&include_adult=false{}", config::TMDB_API_TOKEN, film, year))?.json()?;
-    let results = &data["results"].as_array().unwrap();
-    if !results.is_empty() {
-        let film_id = &results[0]["id"];
-        let title = format!(
-            "{} ({})",
-            &results[0]["original_title"].as_str().unwrap(),
-            &results[0]["release_date"].as_str().unwrap()[..4]
-        );
-        let posters_check = if choice == "posters" {
-            "&language=en-US&include_image_language=en"
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+const IMAGE_BASE_URL: &str = "https://www.themoviedb.org/t/p";
+
+/// What `/movie/{id}` appends to the film, so one request gets everything `/film info` shows.
+const DETAILS_APPENDS: &str = "credits,release_dates,keywords,external_ids";
+
+/// The release type TMDB gives theatrical releases, whose certification is the one people know.
+const THEATRICAL_RELEASE: u8 = 3;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TmdbCandidate {
+    pub id: u64,
+    /// The title in the searched language, the one people type.
+    pub title: String,
+    pub original_title: String,
+    pub year: Option<i32>,
+}
+
+impl TmdbCandidate {
+    pub fn display_title(&self) -> String {
+        match self.year {
+            Some(year) => format!("{} ({})", self.title, year),
+            None => self.title.clone(),
+        }
+    }
+
+    /// The original title, `None` when it's the same as `title`.
+    pub fn original(&self) -> Option<&str> {
+        (self.original_title != self.title).then_some(self.original_title.as_str())
+    }
+}
+
+/// The year of a `2023-03-14` release date, which TMDB sends empty when unknown.
+fn release_year(release_date: Option<&str>) -> Option<i32> {
+    release_date
+        .and_then(|date| date.get(..4))
+        .and_then(|year| year.parse().ok())
+}
+
+fn image_url(size: &str, path: &str) -> String {
+    format!("{}/{}{}", IMAGE_BASE_URL, size, path)
+}
+
+#[derive(Deserialize)]
+struct SearchResults {
+    #[serde(default)]
+    results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    id: u64,
+    title: String,
+    original_title: String,
+    release_date: Option<String>,
+}
+
+impl From<SearchResult> for TmdbCandidate {
+    fn from(result: SearchResult) -> Self {
+        Self {
+            id: result.id,
+            year: release_year(result.release_date.as_deref()),
+            title: result.title,
+            original_title: result.original_title,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Images {
+    #[serde(default)]
+    posters: Vec<Image>,
+    #[serde(default)]
+    backdrops: Vec<Image>,
+}
+
+#[derive(Deserialize)]
+struct Image {
+    file_path: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Genre {
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Language {
+    pub iso_639_1: String,
+    pub english_name: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CastMember {
+    pub name: String,
+    pub character: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CrewMember {
+    pub name: String,
+    pub job: String,
+    pub department: String,
+}
+
+/// Cast in billing order, crew in no particular one.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Credits {
+    #[serde(default)]
+    pub cast: Vec<CastMember>,
+    #[serde(default)]
+    pub crew: Vec<CrewMember>,
+}
+
+/// Departments listed first, the rest follow alphabetically.
+const DEPARTMENTS: [&str; 6] = [
+    "Directing",
+    "Writing",
+    "Production",
+    "Camera",
+    "Editing",
+    "Sound",
+];
+
+impl Credits {
+    /// The crew grouped by department, key departments first.
+    pub fn departments(&self) -> Vec<(&str, Vec<&CrewMember>)> {
+        let mut departments: Vec<(&str, Vec<&CrewMember>)> = vec![];
+        for member in &self.crew {
+            match departments
+                .iter_mut()
+                .find(|(name, _)| *name == member.department)
+            {
+                Some((_, members)) => members.push(member),
+                None => departments.push((&member.department, vec![member])),
+            }
+        }
+        departments.sort_by_key(|(name, _)| {
+            let rank = DEPARTMENTS.iter().position(|d| d == name);
+            (rank.unwrap_or(DEPARTMENTS.len()), *name)
+        });
+        departments
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Release {
+    /// Empty when the release wasn't rated.
+    pub certification: String,
+    #[serde(rename = "type")]
+    pub kind: u8,
+}
+
+/// The releases of a film in one country.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CountryReleases {
+    pub iso_3166_1: String,
+    #[serde(default)]
+    pub release_dates: Vec<Release>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ReleaseDates {
+    #[serde(default)]
+    pub results: Vec<CountryReleases>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Keyword {
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Keywords {
+    #[serde(default)]
+    pub keywords: Vec<Keyword>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ExternalIds {
+    pub imdb_id: Option<String>,
+}
+
+/// A film from `/movie/{id}`, with [`DETAILS_APPENDS`] appended.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TmdbFilm {
+    pub id: u64,
+    pub title: String,
+    pub original_title: String,
+    /// An ISO 639-1 code, e.g. `en`.
+    pub original_language: String,
+    pub release_date: Option<String>,
+    pub tagline: Option<String>,
+    pub overview: Option<String>,
+    /// Runtime in minutes, `0` or missing when unknown.
+    pub runtime: Option<u32>,
+    /// In US dollars, `0` when unknown.
+    #[serde(default)]
+    pub budget: u64,
+    /// In US dollars, `0` when unknown.
+    #[serde(default)]
+    pub revenue: u64,
+    pub poster_path: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<Genre>,
+    #[serde(default)]
+    pub spoken_languages: Vec<Language>,
+    #[serde(default)]
+    pub credits: Credits,
+    #[serde(default)]
+    pub release_dates: ReleaseDates,
+    #[serde(default)]
+    pub keywords: Keywords,
+    #[serde(default)]
+    pub external_ids: ExternalIds,
+}
+
+impl TmdbFilm {
+    pub fn year(&self) -> Option<i32> {
+        release_year(self.release_date.as_deref())
+    }
+
+    /// The title with the release year, e.g. `Alien (1979)`.
+    pub fn display_title(&self) -> String {
+        match self.year() {
+            Some(year) => format!("{} ({})", self.title, year),
+            None => self.title.clone(),
+        }
+    }
+
+    pub fn url(&self) -> String {
+        format!("https://www.themoviedb.org/movie/{}", self.id)
+    }
+
+    /// Letterboxd redirects its `/tmdb/{id}/` links to the film's page.
+    pub fn letterboxd_url(&self) -> String {
+        format!("{}/tmdb/{}/", config::LETTERBOXD_BASE_URL, self.id)
+    }
+
+    pub fn imdb_url(&self) -> Option<String> {
+        self.external_ids
+            .imdb_id
+            .as_ref()
+            .filter(|id| !id.is_empty())
+            .map(|id| format!("https://www.imdb.com/title/{}/", id))
+    }
+
+    pub fn poster(&self) -> Option<String> {
+        self.poster_path
+            .as_ref()
+            .map(|path| image_url("w500", path))
+    }
+
+    /// The English name of the original language, falling back to its code.
+    pub fn language(&self) -> String {
+        self.spoken_languages
+            .iter()
+            .find(|l| l.iso_639_1 == self.original_language && !l.english_name.is_empty())
+            .map(|l| l.english_name.clone())
+            .unwrap_or_else(|| self.original_language.to_uppercase())
+    }
+
+    /// The rating the film got in `country`, e.g. `R` in `US`, preferring its theatrical release.
+    pub fn certification(&self, country: &str) -> Option<&str> {
+        let releases = &self
+            .release_dates
+            .results
+            .iter()
+            .find(|r| r.iso_3166_1 == country)?
+            .release_dates;
+        let rated = |r: &&Release| !r.certification.is_empty();
+        releases
+            .iter()
+            .filter(rated)
+            .find(|r| r.kind == THEATRICAL_RELEASE)
+            .or_else(|| releases.iter().find(rated))
+            .map(|r| r.certification.as_str())
+    }
+
+    /// The names of the crew `matches` picks, each name once, in credits order.
+    fn crew_names(&self, matches: impl Fn(&CrewMember) -> bool) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for member in self.credits.crew.iter().filter(|member| matches(member)) {
+            if !names.contains(&member.name.as_str()) {
+                names.push(&member.name);
+            }
+        }
+        names
+    }
+
+    pub fn directors(&self) -> Vec<&str> {
+        self.crew_names(|member| member.job == "Director")
+    }
+
+    pub fn writers(&self) -> Vec<&str> {
+        self.crew_names(|member| member.department == "Writing")
+    }
+}
+
+#[derive(Deserialize)]
+struct Videos {
+    #[serde(default)]
+    results: Vec<Video>,
+}
+
+/// A trailer, teaser, clip or the like, hosted on YouTube or Vimeo.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Video {
+    pub name: String,
+    /// The id of the video on `site`.
+    pub key: String,
+    pub site: String,
+    /// `Trailer`, `Teaser`, `Clip`, `Featurette`...
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Whether the studio put it out.
+    #[serde(default)]
+    pub official: bool,
+    /// An ISO 639-1 code, e.g. `en`.
+    pub iso_639_1: String,
+}
+
+impl Video {
+    /// `None` for sites videos can't be linked to.
+    pub fn url(&self) -> Option<String> {
+        match self.site.as_str() {
+            "YouTube" => Some(format!("https://www.youtube.com/watch?v={}", self.key)),
+            "Vimeo" => Some(format!("https://vimeo.com/{}", self.key)),
+            _ => None,
+        }
+    }
+}
+
+/// The videos that can be linked to, best first: trailers, then teasers, then clips, then the
+/// rest, each preferring videos in `language`, official ones and YouTube ones in that order.
+pub fn rank_videos(videos: Vec<Video>, language: &str) -> Vec<Video> {
+    let mut videos = videos
+        .into_iter()
+        .filter(|video| video.url().is_some())
+        .collect::<Vec<_>>();
+    // Stable, so TMDB's order breaks ties.
+    videos.sort_by_key(|video| {
+        let kind = ["Trailer", "Teaser", "Clip"]
+            .iter()
+            .position(|kind| *kind == video.kind)
+            .unwrap_or(3);
+        (
+            kind,
+            video.iso_639_1 != language,
+            !video.official,
+            video.site != "YouTube",
+        )
+    });
+    videos
+}
+
+/// Normalizes a country code like `gb` into `GB`, `None` unless it's two letters.
+pub fn region(code: &str) -> Option<String> {
+    let code = code.trim();
+    (code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| code.to_ascii_uppercase())
+}
+
+#[derive(Deserialize)]
+struct WatchProviders {
+    #[serde(default)]
+    results: HashMap<String, RegionProviders>,
+}
+
+/// A streaming service or store.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Provider {
+    pub provider_name: String,
+    pub logo_path: Option<String>,
+    /// Lower comes first.
+    #[serde(default)]
+    pub display_priority: u32,
+}
+
+impl Provider {
+    pub fn logo(&self) -> Option<String> {
+        self.logo_path.as_ref().map(|path| image_url("w92", path))
+    }
+}
+
+/// Where a film can be watched in one country, as JustWatch sees it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RegionProviders {
+    /// TMDB's watch page of the film, which links to each provider through JustWatch.
+    pub link: String,
+    #[serde(default)]
+    pub flatrate: Vec<Provider>,
+    #[serde(default)]
+    pub free: Vec<Provider>,
+    #[serde(default)]
+    pub ads: Vec<Provider>,
+    #[serde(default)]
+    pub rent: Vec<Provider>,
+    #[serde(default)]
+    pub buy: Vec<Provider>,
+}
+
+impl RegionProviders {
+    /// The non-empty kinds of offers with their providers, best placed first.
+    pub fn offers(&self) -> Vec<(&'static str, Vec<&Provider>)> {
+        [
+            ("Stream", &self.flatrate),
+            ("Free", &self.free),
+            ("With ads", &self.ads),
+            ("Rent", &self.rent),
+            ("Buy", &self.buy),
+        ]
+        .into_iter()
+        .filter(|(_, providers)| !providers.is_empty())
+        .map(|(kind, providers)| {
+            let mut providers = providers.iter().collect::<Vec<_>>();
+            providers.sort_by_key(|provider| provider.display_priority);
+            (kind, providers)
+        })
+        .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct PersonResults {
+    #[serde(default)]
+    results: Vec<PersonResult>,
+}
+
+#[derive(Deserialize)]
+struct PersonResult {
+    id: u64,
+    name: String,
+    known_for_department: Option<String>,
+    #[serde(default)]
+    known_for: Vec<KnownFor>,
+}
+
+/// A film or show in a person search result, only one of the names being set.
+#[derive(Deserialize)]
+struct KnownFor {
+    title: Option<String>,
+    name: Option<String>,
+}
+
+/// A person in TMDB's search results.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PersonCandidate {
+    pub id: u64,
+    pub name: String,
+    /// What they're mostly credited for, e.g. `Acting` or `Directing`.
+    pub department: Option<String>,
+    pub known_for: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ActingCredit {
+    pub id: u64,
+    #[serde(default)]
+    pub title: String,
+    pub character: Option<String>,
+    pub release_date: Option<String>,
+    #[serde(default)]
+    pub popularity: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CrewCredit {
+    pub id: u64,
+    #[serde(default)]
+    pub title: String,
+    pub job: String,
+    pub release_date: Option<String>,
+    #[serde(default)]
+    pub popularity: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PersonCredits {
+    #[serde(default)]
+    pub cast: Vec<ActingCredit>,
+    #[serde(default)]
+    pub crew: Vec<CrewCredit>,
+}
+
+/// A film in a filmography.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Credit {
+    /// The film's TMDB id.
+    pub id: u64,
+    pub title: String,
+    pub year: Option<i32>,
+    /// The character played, for acting credits.
+    pub role: Option<String>,
+}
+
+impl Credit {
+    pub fn display_title(&self) -> String {
+        match self.year {
+            Some(year) => format!("{} ({})", self.title, year),
+            None => self.title.clone(),
+        }
+    }
+}
+
+/// Turns credits into a filmography, newest first with undated films last, each film once.
+fn filmography<'a>(
+    credits: impl Iterator<Item = (u64, &'a str, Option<&'a str>, Option<&'a str>)>,
+) -> Vec<Credit> {
+    let mut dated: Vec<(Option<&str>, Credit)> = vec![];
+    for (id, title, release_date, role) in credits {
+        if dated.iter().any(|(_, credit)| credit.id == id) {
+            continue;
+        }
+        let release_date = release_date.filter(|date| !date.is_empty());
+        let credit = Credit {
+            id,
+            title: title.to_string(),
+            year: release_year(release_date),
+            role: role.filter(|role| !role.is_empty()).map(str::to_string),
+        };
+        dated.push((release_date, credit));
+    }
+    // Stable, and `None` sorts before any date, so undated films end up last.
+    dated.sort_by(|(a, _), (b, _)| b.cmp(a));
+    dated.into_iter().map(|(_, credit)| credit).collect()
+}
+
+/// A person from `/person/{id}`, with their film credits appended.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TmdbPerson {
+    pub id: u64,
+    pub name: String,
+    /// Empty when nobody wrote one.
+    #[serde(default)]
+    pub biography: String,
+    /// A `1940-04-25` date.
+    pub birthday: Option<String>,
+    pub deathday: Option<String>,
+    pub place_of_birth: Option<String>,
+    pub profile_path: Option<String>,
+    pub known_for_department: Option<String>,
+    #[serde(default)]
+    pub movie_credits: PersonCredits,
+}
+
+impl TmdbPerson {
+    pub fn url(&self) -> String {
+        format!("https://www.themoviedb.org/person/{}", self.id)
+    }
+
+    pub fn profile(&self) -> Option<String> {
+        self.profile_path
+            .as_ref()
+            .map(|path| image_url("h632", path))
+    }
+
+    /// The films they acted in, newest first.
+    pub fn acting(&self) -> Vec<Credit> {
+        filmography(self.movie_credits.cast.iter().map(|c| {
+            let character = c.character.as_deref();
+            (c.id, c.title.as_str(), c.release_date.as_deref(), character)
+        }))
+    }
+
+    /// The films they directed, newest first.
+    pub fn directing(&self) -> Vec<Credit> {
+        filmography(
+            self.movie_credits
+                .crew
+                .iter()
+                .filter(|c| c.job == "Director")
+                .map(|c| (c.id, c.title.as_str(), c.release_date.as_deref(), None)),
+        )
+    }
+
+    /// Their most popular films in the department they're known for, at most `count`.
+    pub fn known_for(&self, count: usize) -> Vec<&str> {
+        let mut films = if self.known_for_department.as_deref() == Some("Acting") {
+            self.movie_credits
+                .cast
+                .iter()
+                .map(|c| (c.popularity, c.title.as_str()))
+                .collect::<Vec<_>>()
         } else {
-            ""
+            self.movie_credits
+                .crew
+                .iter()
+                .map(|c| (c.popularity, c.title.as_str()))
+                .collect::<Vec<_>>()
         };
-        let images: json::Value = reqwest::blocking::get(format!(
-            "https://api.themoviedb.org/3/movie/{}/images?api_key={}{}",
-            film_id,
-            config::TMDB_API_TOKEN,
-            posters_check
-        ))?
-        .json()?;
-        let posters = &images[choice]
-            .as_array()
-            .unwrap()
-            .iter()
-            .map(|p| {
-                format!(
-                    "https://www.themoviedb.org/t/p/original{}",
-                    p["file_path"].as_str().unwrap()
-                )
+        films.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        let mut titles: Vec<&str> = vec![];
+        for (_, title) in films {
+            if !titles.contains(&title) && titles.len() < count {
+                titles.push(title);
+            }
+        }
+        titles
+    }
+}
+
+/// Async TMDB API client sharing one pooled `reqwest::Client`.
+#[derive(Clone, Debug)]
+pub struct TmdbClient {
+    http: reqwest::Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl Default for TmdbClient {
+    fn default() -> Self {
+        Self::new(config::TMDB_API_TOKEN, config::TMDB_BASE_URL)
+    }
+}
+
+impl TmdbClient {
+    pub fn new(api_key: impl Into<String>, base_url: impl Into<String>) -> Self {
+        let http = reqwest::Client::builder()
+            .user_agent(config::USER_AGENT)
+            .build()
+            .expect("failed to build the TMDB HTTP client");
+        Self {
+            http,
+            api_key: api_key.into(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, Error> {
+        Ok(self
+            .http
+            .get(format!("{}{}", self.base_url, path))
+            .query(&[("api_key", self.api_key.as_str())])
+            .query(query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// Searches films by title, best matches first.
+    pub async fn search(
+        &self,
+        title: &str,
+        year: Option<i32>,
+    ) -> Result<Vec<TmdbCandidate>, Error> {
+        let year = year.map(|y| y.to_string()).unwrap_or_default();
+        let mut query = vec![
+            ("language", "en-US"),
+            ("query", title),
+            ("page", "1"),
+            ("include_adult", "false"),
+        ];
+        if !year.is_empty() {
+            query.push(("year", year.as_str()));
+        }
+        let data: SearchResults = self.get("/search/movie", &query).await?;
+        Ok(data.results.into_iter().map(TmdbCandidate::from).collect())
+    }
+
+    /// Searches people by name, best matches first.
+    pub async fn search_people(&self, name: &str) -> Result<Vec<PersonCandidate>, Error> {
+        let query = [
+            ("language", "en-US"),
+            ("query", name),
+            ("page", "1"),
+            ("include_adult", "false"),
+        ];
+        let data: PersonResults = self.get("/search/person", &query).await?;
+        Ok(data
+            .results
+            .into_iter()
+            .map(|r| PersonCandidate {
+                id: r.id,
+                name: r.name,
+                department: r.known_for_department,
+                known_for: r
+                    .known_for
+                    .into_iter()
+                    .filter_map(|k| k.title.or(k.name))
+                    .collect(),
             })
-            .collect::<Vec<_>>()
-            .to_vec();
-        Ok((title, posters.to_vec()))
-    } else {
-        Ok((String::new(), vec![]))
+            .collect())
+    }
+
+    /// A person's details along with their film credits.
+    pub async fn person(&self, id: u64) -> Result<TmdbPerson, Error> {
+        let query = [
+            ("language", "en-US"),
+            ("append_to_response", "movie_credits"),
+        ];
+        self.get(&format!("/person/{}", id), &query).await
+    }
+
+    /// The first credited director of a film, used to tell same-titled films apart.
+    pub async fn director(&self, id: u64) -> Result<Option<String>, Error> {
+        let credits: Credits = self.get(&format!("/movie/{}/credits", id), &[]).await?;
+        Ok(credits
+            .crew
+            .into_iter()
+            .find(|c| c.job == "Director")
+            .map(|c| c.name))
+    }
+
+    /// A film's details along with its credits, certifications, keywords and IMDb id.
+    pub async fn details(&self, id: u64) -> Result<TmdbFilm, Error> {
+        let query = [
+            ("language", "en-US"),
+            ("append_to_response", DETAILS_APPENDS),
+        ];
+        self.get(&format!("/movie/{}", id), &query).await
+    }
+
+    /// A film's videos in `language` or English, along with those in no language.
+    pub async fn videos(&self, id: u64, language: &str) -> Result<Vec<Video>, Error> {
+        let languages = format!("{},en,null", language);
+        let query = [("include_video_language", languages.as_str())];
+        let videos: Videos = self.get(&format!("/movie/{}/videos", id), &query).await?;
+        Ok(videos.results)
+    }
+
+    /// Where a film can be watched, by country code.
+    pub async fn watch_providers(
+        &self,
+        id: u64,
+    ) -> Result<HashMap<String, RegionProviders>, Error> {
+        let providers: WatchProviders = self
+            .get(&format!("/movie/{}/watch/providers", id), &[])
+            .await?;
+        Ok(providers.results)
+    }
+
+    /// Image URLs of a film, `choice` being either `posters` or `backdrops`.
+    pub async fn images(&self, id: u64, choice: &str) -> Result<Vec<String>, Error> {
+        let query: &[(&str, &str)] = if choice == "posters" {
+            &[("language", "en-US"), ("include_image_language", "en")]
+        } else {
+            &[]
+        };
+        let images: Images = self.get(&format!("/movie/{}/images", id), query).await?;
+        let images = if choice == "posters" {
+            images.posters
+        } else {
+            images.backdrops
+        };
+        Ok(images
+            .iter()
+            .map(|image| image_url("original", &image.file_path))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn film() -> TmdbFilm {
+        serde_json::from_str(include_str!("../../fixtures/tmdb/movie.json")).unwrap()
+    }
+
+    #[test]
+    fn film_details() {
+        let film = film();
+        assert_eq!(film.display_title(), "Heat (1995)");
+        assert_eq!(film.runtime, Some(170));
+        assert_eq!(film.budget, 60_000_000);
+        assert_eq!(film.language(), "English");
+        assert_eq!(film.certification("US"), Some("R"));
+        assert_eq!(film.certification("FR"), Some("12"));
+        assert_eq!(film.certification("JP"), None);
+        assert_eq!(film.directors(), vec!["Michael Mann"]);
+        assert_eq!(film.writers(), vec!["Michael Mann"]);
+        assert_eq!(film.credits.cast[0].name, "Al Pacino");
+        let departments = film.credits.departments();
+        let names = departments
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Directing", "Writing", "Production", "Sound"]);
+        assert_eq!(film.keywords.keywords.len(), 3);
+        assert_eq!(
+            film.imdb_url().as_deref(),
+            Some("https://www.imdb.com/title/tt0113277/")
+        );
+        assert_eq!(film.letterboxd_url(), "https://letterboxd.com/tmdb/949/");
+        assert_eq!(
+            film.poster().as_deref(),
+            Some("https://www.themoviedb.org/t/p/w500/umSVjVdbVwtx5ryCA2QXL44Durm.jpg")
+        );
+    }
+
+    fn video(key: &str, kind: &str, language: &str, official: bool, site: &str) -> Video {
+        Video {
+            name: key.to_string(),
+            key: key.to_string(),
+            site: site.to_string(),
+            kind: kind.to_string(),
+            official,
+            iso_639_1: language.to_string(),
+        }
+    }
+
+    #[test]
+    fn ranks_videos() {
+        let videos = vec![
+            video("clip", "Clip", "fr", true, "YouTube"),
+            video("fan-trailer", "Trailer", "fr", false, "YouTube"),
+            video("english-trailer", "Trailer", "en", true, "YouTube"),
+            video("vimeo-trailer", "Trailer", "fr", true, "Vimeo"),
+            video("trailer", "Trailer", "fr", true, "YouTube"),
+            video("teaser", "Teaser", "fr", true, "YouTube"),
+            video("featurette", "Featurette", "fr", true, "YouTube"),
+            video("unlinkable", "Trailer", "fr", true, "Dailymotion"),
+        ];
+        let ranked = rank_videos(videos, "fr")
+            .into_iter()
+            .map(|video| video.key)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranked,
+            vec![
+                "trailer",
+                "vimeo-trailer",
+                "fan-trailer",
+                "english-trailer",
+                "teaser",
+                "clip",
+                "featurette"
+            ]
+        );
+    }
+
+    #[test]
+    fn candidate_titles() {
+        let results: SearchResults = serde_json::from_str(
+            r#"{"results": [
+                {"id": 1, "title": "Spirited Away", "original_title": "千と千尋の神隠し",
+                 "release_date": "2001-07-20"},
+                {"id": 2, "title": "Heat", "original_title": "Heat", "release_date": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let candidates = results
+            .results
+            .into_iter()
+            .map(TmdbCandidate::from)
+            .collect::<Vec<_>>();
+        assert_eq!(candidates[0].display_title(), "Spirited Away (2001)");
+        assert_eq!(candidates[0].original(), Some("千と千尋の神隠し"));
+        assert_eq!(candidates[1].display_title(), "Heat");
+        assert_eq!(candidates[1].original(), None);
+    }
+
+    #[test]
+    fn regions() {
+        assert_eq!(region(" gb ").as_deref(), Some("GB"));
+        assert_eq!(region("US").as_deref(), Some("US"));
+        assert_eq!(region("USA"), None);
+        assert_eq!(region("1A"), None);
+    }
+
+    #[test]
+    fn watch_offers() {
+        let providers: WatchProviders = serde_json::from_str(
+            r#"{"id": 949, "results": {"US": {
+                "link": "https://www.themoviedb.org/movie/949-heat/watch?locale=US",
+                "flatrate": [
+                    {"provider_name": "Hulu", "logo_path": "/hulu.jpg", "display_priority": 6},
+                    {"provider_name": "Max", "logo_path": "/max.jpg", "display_priority": 2}
+                ],
+                "buy": [
+                    {"provider_name": "Apple TV", "logo_path": "/apple.jpg", "display_priority": 4}
+                ]
+            }}}"#,
+        )
+        .unwrap();
+        let us = &providers.results["US"];
+        let offers = us.offers();
+        assert_eq!(offers.len(), 2);
+        assert_eq!(offers[0].0, "Stream");
+        assert_eq!(offers[0].1[0].provider_name, "Max");
+        assert_eq!(offers[1].0, "Buy");
+        assert_eq!(
+            offers[0].1[0].logo().as_deref(),
+            Some("https://www.themoviedb.org/t/p/w92/max.jpg")
+        );
+    }
+
+    #[test]
+    fn filmographies() {
+        let person: TmdbPerson = serde_json::from_str(
+            r#"{"id": 638, "name": "Michael Mann", "biography": "",
+                "known_for_department": "Directing",
+                "movie_credits": {
+                    "cast": [
+                        {"id": 1, "title": "Cameo", "character": "Himself",
+                         "release_date": "2001-05-01", "popularity": 1.0}
+                    ],
+                    "crew": [
+                        {"id": 949, "title": "Heat", "job": "Director",
+                         "release_date": "1995-12-15", "popularity": 40.0},
+                        {"id": 949, "title": "Heat", "job": "Screenplay",
+                         "release_date": "1995-12-15", "popularity": 40.0},
+                        {"id": 2, "title": "Untitled Project", "job": "Director",
+                         "release_date": "", "popularity": 2.0},
+                        {"id": 1538, "title": "Collateral", "job": "Director",
+                         "release_date": "2004-08-05", "popularity": 30.0},
+                        {"id": 3, "title": "Produced Film", "job": "Producer",
+                         "release_date": "2010-01-01", "popularity": 50.0}
+                    ]
+                }}"#,
+        )
+        .unwrap();
+        let directing = person.directing();
+        assert_eq!(
+            directing
+                .iter()
+                .map(|credit| credit.display_title())
+                .collect::<Vec<_>>(),
+            vec!["Collateral (2004)", "Heat (1995)", "Untitled Project"]
+        );
+        assert_eq!(
+            person.acting(),
+            vec![Credit {
+                id: 1,
+                title: "Cameo".to_string(),
+                year: Some(2001),
+                role: Some("Himself".to_string()),
+            }]
+        );
+        assert_eq!(
+            person.known_for(3),
+            vec!["Produced Film", "Heat", "Collateral"]
+        );
+    }
+
+    #[test]
+    fn sparse_film() {
+        let film: TmdbFilm = serde_json::from_str(
+            r#"{"id": 1, "title": "Untitled", "original_title": "Untitled",
+                "original_language": "xx", "release_date": "", "runtime": null}"#,
+        )
+        .unwrap();
+        assert_eq!(film.display_title(), "Untitled");
+        assert_eq!(film.language(), "XX");
+        assert!(film.imdb_url().is_none());
+        assert!(film.certification("US").is_none());
+        assert!(film.directors().is_empty());
     }
 }
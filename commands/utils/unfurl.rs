@@ -0,0 +1,232 @@
+//! Answers Letterboxd links posted in chat with the bot's embeds, in guilds that opted in.
+
+use crate::commands::utils::{errors::ScrapeError, render, structs::Data};
+use crate::config;
+
+use poise::serenity_prelude as serenity;
+use regex::Regex;
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+/// First path segments that aren't usernames.
+const RESERVED: [&str; 16] = [
+    "about", "activity", "actor", "apps", "contact", "director", "film", "films", "journal",
+    "lists", "members", "pro", "search", "settings", "sign-in", "tag",
+];
+
+/// Characters ending a matched link that belong to the sentence around it.
+const TRAILING: [char; 7] = ['.', ',', ';', ':', '!', '?', ')'];
+
+/// A Letterboxd page linked in a message.
+#[derive(Debug, PartialEq)]
+pub enum Link {
+    /// A film's path, e.g. `/film/alien/`.
+    Film(String),
+    Review { username: String, film_path: String },
+    List { username: String, slug: String },
+    Profile(String),
+    /// A `boxd.it` link, which has to be followed to know what it points at.
+    Short(String),
+}
+
+/// Figures out what page `url` is, `None` for pages without an embed.
+pub fn classify(url: &str) -> Option<Link> {
+    let (host, path) = url.split_once("://")?.1.split_once('/')?;
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let segments = path.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+    if host == "boxd.it" {
+        return (segments.len() == 1).then(|| Link::Short(url.to_string()));
+    }
+    match segments[..] {
+        ["film", slug, ..] => Some(Link::Film(format!("/film/{}/", slug))),
+        [username, "film", slug, ..] => Some(Link::Review {
+            username: username.to_lowercase(),
+            film_path: format!("/film/{}/", slug),
+        }),
+        [username, "list", slug, ..] => Some(Link::List {
+            username: username.to_lowercase(),
+            slug: slug.to_string(),
+        }),
+        [username] if !RESERVED.contains(&username) => Some(Link::Profile(username.to_lowercase())),
+        _ => None,
+    }
+}
+
+/// The Letterboxd links of `content`, at most [`config::UNFURL_MAX_LINKS`] of them.
+pub fn find_links(content: &str) -> Vec<Link> {
+    let url_regex =
+        Regex::new(r#"https?://(?:www\.)?(?:letterboxd\.com|boxd\.it)/[^\s<>|]*"#).unwrap();
+    url_regex
+        .find_iter(content)
+        // Discord doesn't embed links wrapped in `<>`, neither should we.
+        .filter(|m| !content[..m.start()].ends_with('<'))
+        // Punctuation closing a sentence isn't part of the link.
+        .filter_map(|m| classify(m.as_str().trim_end_matches(TRAILING)))
+        .take(config::UNFURL_MAX_LINKS)
+        .collect()
+}
+
+/// Remembers when each channel last got links answered.
+#[derive(Debug, Default)]
+pub struct Cooldown {
+    last: Mutex<HashMap<u64, Instant>>,
+}
+
+impl Cooldown {
+    /// Whether `channel` is off cooldown, starting a new one if it is.
+    pub fn try_start(&self, channel: u64) -> bool {
+        let mut last = self.last.lock().unwrap();
+        let now = Instant::now();
+        if let Some(at) = last.get(&channel) {
+            if now.duration_since(*at) < config::UNFURL_COOLDOWN {
+                return false;
+            }
+        }
+        last.insert(channel, now);
+        true
+    }
+}
+
+/// The embed of the page `link` points at, `None` when there's nothing to show.
+async fn embed(data: &Data, link: Link) -> Result<Option<serenity::CreateEmbed>, ScrapeError> {
+    let link = match link {
+        Link::Short(url) => match classify(&data.lbxd.resolve_short_link(&url).await?) {
+            Some(Link::Short(_)) | None => return Ok(None),
+            Some(link) => link,
+        },
+        link => link,
+    };
+    let color = render::LETTERBOXD_GREEN;
+    let mut e = serenity::CreateEmbed::default();
+    match link {
+        Link::Film(path) => {
+            render::film(&mut e, &data.film_at(&path).await?, color);
+        }
+        Link::Review {
+            username,
+            film_path,
+        } => {
+            render::review(&mut e, &data.review(&username, &film_path).await?, color);
+        }
+        Link::List { username, slug } => {
+            let list = data.list(&username, &slug).await?;
+            render::list(&mut e, &list, data.lbxd.base_url(), color);
+        }
+        Link::Profile(username) => {
+            render::profile(&mut e, &data.profile(&username).await?, color);
+        }
+        Link::Short(_) => return Ok(None),
+    }
+    Ok(Some(e))
+}
+
+/// Answers the Letterboxd links of `message` if its guild opted in.
+pub async fn handle(ctx: &serenity::Context, data: &Data, message: &serenity::Message) {
+    if message.author.bot {
+        return;
+    }
+    let Some(guild_id) = message.guild_id else {
+        return;
+    };
+    let enabled = data
+        .guilds
+        .get(&guild_id.to_string())
+        .is_some_and(|settings| settings.unfurl);
+    if !enabled {
+        return;
+    }
+    let links = find_links(&message.content);
+    if links.is_empty() || !data.unfurl_cooldown.try_start(*message.channel_id.as_u64()) {
+        return;
+    }
+    let mut embeds = vec![];
+    for link in links {
+        match embed(data, link).await {
+            Ok(Some(embed)) => embeds.push(embed),
+            Ok(None) => {}
+            // Nobody asked for these, so failures aren't worth a message in chat.
+            Err(why) => eprintln!("Unfurl: {}", why),
+        }
+    }
+    if embeds.is_empty() {
+        return;
+    }
+    let sent = message
+        .channel_id
+        .send_message(&ctx.http, |m| {
+            m.reference_message(message)
+                .allowed_mentions(|a| a.replied_user(false))
+                .add_embeds(embeds)
+        })
+        .await;
+    if let Err(why) = sent {
+        eprintln!(
+            "Unfurl: couldn't reply in channel {}: {}",
+            message.channel_id, why
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_links() {
+        assert_eq!(
+            classify("https://letterboxd.com/film/alien/"),
+            Some(Link::Film("/film/alien/".to_string()))
+        );
+        assert_eq!(
+            classify("https://letterboxd.com/JaneDoe/film/alien/1/"),
+            Some(Link::Review {
+                username: "janedoe".to_string(),
+                film_path: "/film/alien/".to_string()
+            })
+        );
+        assert_eq!(
+            classify("https://letterboxd.com/janedoe/list/best-of-the-80s/detail/"),
+            Some(Link::List {
+                username: "janedoe".to_string(),
+                slug: "best-of-the-80s".to_string()
+            })
+        );
+        assert_eq!(
+            classify("https://letterboxd.com/janedoe/?ref=share"),
+            Some(Link::Profile("janedoe".to_string()))
+        );
+        assert_eq!(
+            classify("https://boxd.it/1a2B"),
+            Some(Link::Short("https://boxd.it/1a2B".to_string()))
+        );
+        assert_eq!(classify("https://letterboxd.com/films/popular/"), None);
+        assert_eq!(classify("https://letterboxd.com/search/"), None);
+    }
+
+    #[test]
+    fn finds_links_in_messages() {
+        let links = find_links(
+            "watch https://letterboxd.com/film/alien/ tonight, not \
+             <https://letterboxd.com/film/heat-1995/>, ||https://boxd.it/1a2B||",
+        );
+        assert_eq!(
+            links,
+            vec![
+                Link::Film("/film/alien/".to_string()),
+                Link::Short("https://boxd.it/1a2B".to_string()),
+            ]
+        );
+        assert_eq!(
+            find_links("(see https://letterboxd.com/janedoe/)."),
+            vec![Link::Profile("janedoe".to_string())]
+        );
+        assert!(find_links("no links here").is_empty());
+    }
+
+    #[test]
+    fn cools_down_per_channel() {
+        let cooldown = Cooldown::default();
+        assert!(cooldown.try_start(1));
+        assert!(!cooldown.try_start(1));
+        assert!(cooldown.try_start(2));
+    }
+}
@@ -1,6 +1,139 @@
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 pub const DISCORD_TOKEN: &str =
     "";
 
 pub const TMDB_API_TOKEN: &str = "";
+
+pub const TMDB_BASE_URL: &str = "https://api.themoviedb.org/3";
+
+pub const LETTERBOXD_BASE_URL: &str = "https://letterboxd.com";
+
+/// The country whose certifications and watch providers are shown, unless a guild picked
+/// another.
+pub const DEFAULT_REGION: &str = "US";
+
+pub const USER_AGENT: &str = concat!("filmbro/", env!("CARGO_PKG_VERSION"));
+
+/// Where warm caches are saved between restarts, `None` keeps them in memory only.
+pub const CACHE_DIR: Option<&str> = Some("cache");
+
+/// How often changed caches are written to [`CACHE_DIR`], which is at most how much a crash
+/// loses.
+pub const CACHE_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Where data that can't be rebuilt, like linked accounts, is saved. `None` keeps it in
+/// memory only.
+pub const DATA_DIR: Option<&str> = Some("data");
+
+/// How many entries each cache holds before evicting the least recently used one.
+pub const CACHE_CAPACITY: usize = 500;
+
+/// How many entries one Letterboxd diary page lists.
+pub const DIARY_PAGE_ENTRIES: u32 = 50;
+
+/// How many diary pages ([`DIARY_PAGE_ENTRIES`] each) browsing a diary fetches at most.
+pub const DIARY_MAX_PAGES: u32 = 5;
+
+/// How many cast or crew members one paginator page shows.
+pub const CREDITS_PER_PAGE: usize = 15;
+
+/// How many films of a filmography one paginator page shows, each getting a button.
+pub const FILMOGRAPHY_PER_PAGE: usize = 5;
+
+/// How many diary entries one paginator page shows.
+pub const DIARY_ENTRIES_PER_PAGE: usize = 10;
+
+/// How many pages of a list are fetched at most, longer lists get cut short.
+pub const LIST_MAX_PAGES: u32 = 10;
+
+/// How many list entries one paginator page shows.
+pub const LIST_ENTRIES_PER_PAGE: usize = 10;
+
+/// How many films one Letterboxd watchlist page shows.
+pub const WATCHLIST_PAGE_FILMS: u32 = 28;
+
+/// How many watchlist pages ([`WATCHLIST_PAGE_FILMS`] each) are fetched at most.
+pub const WATCHLIST_MAX_PAGES: u32 = 20;
+
+/// How many rating pages (72 films each) comparing members fetches at most per member.
+pub const RATINGS_MAX_PAGES: u32 = 15;
+
+/// The furthest browse page the roulette draws from, later pages having obscure films.
+pub const ROULETTE_MAX_PAGE: u32 = 20;
+
+/// How many films of a browse page the roulette looks up at most for one that matches.
+pub const ROULETTE_MAX_LOOKUPS: usize = 8;
+
+/// How many films filtering by runtime or genre looks up at most, each lookup being two
+/// Letterboxd requests unless the film is cached.
+pub const FILM_FILTER_MAX_LOOKUPS: usize = 50;
+
+/// How many films filtering looks up at once.
+pub const FILM_FILTER_CONCURRENCY: usize = 5;
+
+/// How many matching films filtering stops at, three pages' worth.
+pub const FILM_FILTER_MAX_MATCHES: usize = 30;
+
+pub const DIARY_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+pub const PROFILE_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+pub const REVIEW_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+pub const LIST_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+pub const WATCHLIST_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Ratings take many pages to fetch and change slowly, so they're kept longer.
+pub const RATINGS_CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Streaming catalogs change often, so where to watch a film isn't kept long.
+pub const WATCH_CACHE_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+
+pub const PERSON_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub const FILM_CACHE_TTL: Duration = Duration::from_secs(3 * 24 * 60 * 60);
+
+pub const IMAGE_CACHE_TTL: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+pub const SUGGESTION_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long a looked up username keeps being suggested.
+pub const USERNAME_TTL: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// How often followed diaries are checked for new entries.
+pub const FEED_POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Pause between two diary fetches of the same poll, to go easy on Letterboxd.
+pub const FEED_FETCH_DELAY: Duration = Duration::from_secs(5);
+
+/// The longest the feed watcher backs off for after being rate limited or failing.
+pub const FEED_MAX_BACKOFF: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// How long the review buttons of a diary embed keep working.
+pub const REVIEW_BUTTON_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// How long a roulette spins before giving up.
+pub const ROULETTE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long the reroll button of a roulette result keeps working.
+pub const ROULETTE_REROLL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// How long a channel waits before its Letterboxd links get answered again.
+pub const UNFURL_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// How many Letterboxd links of one message get answered at most.
+pub const UNFURL_MAX_LINKS: usize = 3;
+
+/// How many years past the current one films can be looked up for, announced films having
+/// release years that far out.
+pub const UPCOMING_RELEASE_YEARS: i32 = 5;
+
+/// How many diaries a guild can follow.
+pub const FEED_MAX_FOLLOWS: usize = 25;
+
+/// How long to wait for the next keystroke before searching TMDB for suggestions.
+pub const AUTOCOMPLETE_DEBOUNCE: Duration = Duration::from_millis(300);
@@ -3,7 +3,7 @@ use poise::serenity_prelude as serenity;
 mod commands;
 mod config;
 use commands::cmds;
-use commands::utils::structs::Data;
+use commands::utils::{cache, feed, structs::Data, unfurl};
 
 //type Context<'a> = poise::Context<'a, Data, Error>;
 type Error = Box<dyn std::error::Error + Send + Sync>;
@@ -12,7 +12,7 @@ async fn event_handlers(
     ctx: &serenity::Context,
     event: &poise::Event<'_>,
     _framework: poise::FrameworkContext<'_, Data, Error>,
-    _user_data: &Data,
+    user_data: &Data,
 ) -> Result<(), Error> {
     match event {
         poise::Event::Ready { data_about_bot } => {
@@ -22,6 +22,15 @@ async fn event_handlers(
             )
             .await?;
             println!("Logged in: {}", data_about_bot.user.tag());
+            feed::spawn(
+                ctx.http.clone(),
+                user_data.lbxd.clone(),
+                user_data.guilds.clone(),
+            );
+            cache::spawn_flusher(user_data.flushers());
+        }
+        poise::Event::Message { new_message } => {
+            unfurl::handle(ctx, user_data, new_message).await;
         }
         _ => {}
     }